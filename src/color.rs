@@ -1,4 +1,5 @@
 use std::convert::From;
+use std::fmt;
 use std::str::FromStr;
 
 /// A color that can be one of the 8 standard color or any RGB color.
@@ -13,9 +14,20 @@ pub enum AllColor {
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct TrueColor(u8, u8, u8);
 
+/// The linear intensity of an sRGB channel, from 0 to 1. WCAG 2 writes the threshold 0.03928,
+/// from an old draft of sRGB: both give the same result for 8 bits channels.
+pub(crate) fn to_linear(channel: u8) -> f64 {
+    let c = f64::from(channel) / 255.0;
+    if c <= 0.040_45 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
 impl TrueColor {
     /// Creates a new RGB color from its channels values.
-    pub fn new(r: u8, g: u8, b: u8) -> TrueColor {
+    pub const fn new(r: u8, g: u8, b: u8) -> TrueColor {
         TrueColor(r, g, b)
     }
 
@@ -34,6 +46,52 @@ impl TrueColor {
         TrueColor(r as u8, g as u8, b as u8)
     }
 
+    pub const fn r(self) -> u8 {
+        self.0
+    }
+
+    pub const fn g(self) -> u8 {
+        self.1
+    }
+
+    pub const fn b(self) -> u8 {
+        self.2
+    }
+
+    /// The relative luminance of the color as defined by WCAG 2, from 0 for black to 1 for
+    /// white.
+    pub fn relative_luminance(self) -> f64 {
+        0.2126 * to_linear(self.0) + 0.7152 * to_linear(self.1) + 0.0722 * to_linear(self.2)
+    }
+
+    /// The contrast ratio between two colors as defined by WCAG 2, from 1 for the same
+    /// luminance to 21 for black and white. Text should have a ratio of at least 4.5 with its
+    /// background (level AA), or 3 when it is large.
+    ///
+    /// # Example
+    /// ```
+    /// # use colored::TrueColor;
+    /// let black = TrueColor::new(0, 0, 0);
+    /// assert_eq!(21.0, black.contrast_ratio(TrueColor::new(255, 255, 255)));
+    /// assert!(TrueColor::new(255, 255, 0).contrast_ratio(TrueColor::new(255, 255, 255)) < 4.5);
+    /// ```
+    pub fn contrast_ratio(self, other: TrueColor) -> f64 {
+        let (l1, l2) = (self.relative_luminance(), other.relative_luminance());
+        (l1.max(l2) + 0.05) / (l1.min(l2) + 0.05)
+    }
+
+    /// Black or white, the one most readable on this color. The contrast ratio is then always
+    /// at least 4.58, more than WCAG AA asks for.
+    pub fn readable_foreground(self) -> TrueColor {
+        let black = TrueColor(0, 0, 0);
+        let white = TrueColor(255, 255, 255);
+        if self.contrast_ratio(black) >= self.contrast_ratio(white) {
+            black
+        } else {
+            white
+        }
+    }
+
     pub fn to_fg_string(self) -> String {
         format!("38;2;{};{};{}", self.0, self.1, self.2)
     }
@@ -41,6 +99,69 @@ impl TrueColor {
     pub fn to_bg_string(self) -> String {
         format!("48;2;{};{};{}", self.0, self.1, self.2)
     }
+
+    pub fn to_underline_string(self) -> String {
+        format!("58;2;{};{};{}", self.0, self.1, self.2)
+    }
+}
+
+impl fmt::Display for TrueColor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// Parses `"#rrggbb"` and `"#rgb"` colors.
+impl FromStr for TrueColor {
+    type Err = ();
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let hex = match src.strip_prefix('#') {
+            Some(hex) if hex.chars().all(|c| c.is_ascii_hexdigit()) => hex,
+            _ => return Err(()),
+        };
+
+        match hex.len() {
+            6 => u64::from_str_radix(hex, 16)
+                .map(TrueColor::from_hex)
+                .map_err(|_| ()),
+            3 => {
+                // "#abc" is a short for "#aabbcc"
+                let doubled: String = hex.chars().flat_map(|c| vec![c, c]).collect();
+                u64::from_str_radix(&doubled, 16)
+                    .map(TrueColor::from_hex)
+                    .map_err(|_| ())
+            }
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for AllColor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AllColor::Color(c) => c.fmt(f),
+            AllColor::True(c) => c.fmt(f),
+            AllColor::Palette(code) => code.fmt(f),
+        }
+    }
+}
+
+/// Parses a color name (`"bright red"`), an RGB color (`"#ff0000"`) or a palette index (`"196"`).
+impl FromStr for AllColor {
+    type Err = ();
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let src = src.trim();
+
+        if let Ok(color) = src.parse() {
+            Ok(AllColor::Color(color))
+        } else if let Ok(color) = src.parse() {
+            Ok(AllColor::True(color))
+        } else {
+            src.parse().map(AllColor::Palette).map_err(|_| ())
+        }
+    }
 }
 
 
@@ -86,6 +207,59 @@ impl Color {
         }
     }
 
+    /// The index of this color in the 256 colors palette. Used where the terminal only accepts
+    /// palette or RGB colors, like the underline color (SGR 58).
+    pub fn to_palette_index(&self) -> u8 {
+        match *self {
+            Color::Black => 0,
+            Color::Red => 1,
+            Color::Green => 2,
+            Color::Yellow => 3,
+            Color::Blue => 4,
+            Color::Magenta => 5,
+            Color::Cyan => 6,
+            Color::White => 7,
+            Color::BrightBlack => 8,
+            Color::BrightRed => 9,
+            Color::BrightGreen => 10,
+            Color::BrightYellow => 11,
+            Color::BrightBlue => 12,
+            Color::BrightMagenta => 13,
+            Color::BrightCyan => 14,
+            Color::BrightWhite => 15,
+        }
+    }
+
+    /// The RGB value of this color in xterm, the default of most terminals. See
+    /// `palette::Palette` for the values in the other terminals.
+    pub fn to_true_color(&self) -> TrueColor {
+        ::palette::XTERM_COLORS[usize::from(self.to_palette_index())]
+    }
+
+    /// The color at this index of the 256 colors palette, if it is one of the 16 standard ones.
+    pub fn from_palette_index(index: u8) -> Option<Color> {
+        let color = match index {
+            0 => Color::Black,
+            1 => Color::Red,
+            2 => Color::Green,
+            3 => Color::Yellow,
+            4 => Color::Blue,
+            5 => Color::Magenta,
+            6 => Color::Cyan,
+            7 => Color::White,
+            8 => Color::BrightBlack,
+            9 => Color::BrightRed,
+            10 => Color::BrightGreen,
+            11 => Color::BrightYellow,
+            12 => Color::BrightBlue,
+            13 => Color::BrightMagenta,
+            14 => Color::BrightCyan,
+            15 => Color::BrightWhite,
+            _ => return None,
+        };
+        Some(color)
+    }
+
     pub fn to_bg_str(&self) -> &str {
         match *self {
             Color::Black => "40",
@@ -108,7 +282,31 @@ impl Color {
     }
 }
 
-impl<'a> From<&'a str> for Color {
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            Color::Black => "black",
+            Color::Red => "red",
+            Color::Green => "green",
+            Color::Yellow => "yellow",
+            Color::Blue => "blue",
+            Color::Magenta => "magenta",
+            Color::Cyan => "cyan",
+            Color::White => "white",
+            Color::BrightBlack => "bright black",
+            Color::BrightRed => "bright red",
+            Color::BrightGreen => "bright green",
+            Color::BrightYellow => "bright yellow",
+            Color::BrightBlue => "bright blue",
+            Color::BrightMagenta => "bright magenta",
+            Color::BrightCyan => "bright cyan",
+            Color::BrightWhite => "bright white",
+        };
+        f.write_str(name)
+    }
+}
+
+impl From<&str> for Color {
     fn from(src: &str) -> Self {
         src.parse().unwrap_or(Color::White)
     }
@@ -152,6 +350,36 @@ impl FromStr for Color {
 mod tests {
     pub use super::*;
 
+    #[test]
+    fn contrast() {
+        let black = TrueColor::new(0, 0, 0);
+        let white = TrueColor::new(255, 255, 255);
+
+        assert_eq!(0.0, black.relative_luminance());
+        assert_eq!(1.0, white.relative_luminance());
+        assert_eq!(1.0, white.contrast_ratio(white));
+        let gray = TrueColor::new(0x76, 0x76, 0x76);
+        let ratio = gray.contrast_ratio(white);
+        assert!((ratio - 4.54).abs() < 0.01);
+        assert_eq!(ratio, white.contrast_ratio(gray));
+    }
+
+    #[test]
+    fn xterm_values() {
+        assert_eq!(TrueColor::new(0xcd, 0, 0), Color::Red.to_true_color());
+        assert_eq!(TrueColor::new(0x5c, 0x5c, 0xff), Color::BrightBlue.to_true_color());
+    }
+
+    #[test]
+    fn readable_foreground() {
+        let black = TrueColor::new(0, 0, 0);
+        let white = TrueColor::new(255, 255, 255);
+
+        assert_eq!(white, black.readable_foreground());
+        assert_eq!(black, TrueColor::new(255, 255, 0).readable_foreground());
+        assert_eq!(white, TrueColor::new(0, 0, 255).readable_foreground());
+    }
+
     mod from_str {
         pub use super::*;
 
@@ -250,6 +478,52 @@ mod tests {
 
     }
 
+    mod display {
+        pub use super::*;
+
+        #[test]
+        fn roundtrip() {
+            let colors = [
+                AllColor::Color(Color::Red),
+                AllColor::Color(Color::BrightMagenta),
+                AllColor::True(TrueColor::new(171, 205, 239)),
+                AllColor::True(TrueColor::new(0, 0, 0)),
+                AllColor::Palette(57),
+            ];
+
+            for color in colors.iter() {
+                assert_eq!(Ok(*color), color.to_string().parse());
+            }
+        }
+
+        #[test]
+        fn true_color() {
+            assert_eq!("#abcdef", TrueColor::new(171, 205, 239).to_string());
+            assert_eq!("#01ff00", TrueColor::new(1, 255, 0).to_string());
+        }
+    }
+
+    mod all_color_from_str {
+        pub use super::*;
+
+        #[test]
+        fn parse() {
+            assert_eq!(Ok(AllColor::Color(Color::BrightBlue)), " Bright Blue ".parse());
+            assert_eq!(Ok(AllColor::True(TrueColor::new(171, 205, 239))), "#ABCDEF".parse());
+            assert_eq!(Ok(AllColor::True(TrueColor::new(170, 187, 204))), "#abc".parse());
+            assert_eq!(Ok(AllColor::Palette(255)), "255".parse());
+        }
+
+        #[test]
+        fn error() {
+            let invalid = ["zorglub", "#abcd", "#ghijkl", "256", "", "#"];
+
+            for src in invalid.iter() {
+                assert_eq!(Err(()), src.parse::<AllColor>(), "{:?}", src);
+            }
+        }
+    }
+
     mod hex {
 
         pub use super::*;
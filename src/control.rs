@@ -2,7 +2,7 @@
 
 use std::default::Default;
 use std::env;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 #[cfg(windows)]
 use winconsole::{console, errors::WinResult};
 
@@ -142,6 +142,125 @@ impl ShouldColorize {
     }
 }
 
+/// Whether `Colorize::hyperlink` emits links (OSC 8), which many terminals can't display.
+pub struct ShouldHyperlink {
+    detected: bool,
+    has_manual_override: AtomicBool,
+    manual_override: AtomicBool,
+}
+
+/// Use this to force colored to always/never emit hyperlinks, whatever the terminal. Links are
+/// never emitted when colorization is disabled.
+pub fn set_hyperlink_override(override_hyperlink: bool) {
+    SHOULD_HYPERLINK.set_override(override_hyperlink)
+}
+
+/// Remove the manual override and let the environment decide if the terminal supports links
+pub fn unset_hyperlink_override() {
+    SHOULD_HYPERLINK.unset_override()
+}
+
+lazy_static! {
+    pub static ref SHOULD_HYPERLINK: ShouldHyperlink = ShouldHyperlink::from_env();
+}
+
+impl ShouldHyperlink {
+    /// Detects the support of hyperlinks from the environment. `FORCE_HYPERLINK` decides when
+    /// set (`0` disables them), otherwise the known terminals are looked for.
+    pub fn from_env() -> Self {
+        ShouldHyperlink {
+            detected: ShouldHyperlink::detect(|name| env::var(name).ok()),
+            has_manual_override: AtomicBool::new(false),
+            manual_override: AtomicBool::new(false),
+        }
+    }
+
+    pub fn should_hyperlink(&self) -> bool {
+        if self.has_manual_override.load(Ordering::Relaxed) {
+            return self.manual_override.load(Ordering::Relaxed);
+        }
+
+        self.detected
+    }
+
+    pub fn set_override(&self, override_hyperlink: bool) {
+        self.has_manual_override.store(true, Ordering::Relaxed);
+        self.manual_override
+            .store(override_hyperlink, Ordering::Relaxed);
+    }
+
+    pub fn unset_override(&self) {
+        self.has_manual_override.store(false, Ordering::Relaxed);
+    }
+
+    /* private */
+
+    fn detect<F: Fn(&str) -> Option<String>>(var: F) -> bool {
+        if let Some(force) = var("FORCE_HYPERLINK") {
+            return force != "0";
+        }
+        if var("CI").is_some() {
+            return false;
+        }
+
+        let term_program = var("TERM_PROGRAM").unwrap_or_default();
+        let term = var("TERM").unwrap_or_default();
+        let vte_version = var("VTE_VERSION")
+            .and_then(|version| version.parse::<u32>().ok())
+            .unwrap_or(0);
+
+        ["iTerm.app", "WezTerm", "vscode", "Hyper", "ghostty", "terminology"]
+            .contains(&term_program.as_str())
+            || ["xterm-kitty", "alacritty", "foot", "xterm-ghostty"]
+                .iter()
+                .any(|name| term.starts_with(name))
+            // VTE (GNOME Terminal, Tilix...) supports them since 0.50
+            || vte_version >= 5000
+            || var("WT_SESSION").is_some()
+            || var("KONSOLE_VERSION").is_some()
+            || var("DOMTERM").is_some()
+    }
+}
+
+/// The contrast ratio under which `ColoredString`s are warned about, as the bits of a `f64`, 0
+/// when they aren't checked.
+static MIN_CONTRAST: AtomicU64 = AtomicU64::new(0);
+
+/// A debugging aid: prints a warning on the standard error when a `ColoredString` with both a
+/// foreground and a background color is displayed, and their contrast ratio is under `ratio`
+/// (4.5 for WCAG AA). Each pair of colors is only warned about once. The standard and palette
+/// colors are taken with their xterm values.
+pub fn set_min_contrast(ratio: f64) {
+    MIN_CONTRAST.store(ratio.to_bits(), Ordering::Relaxed);
+}
+
+/// Stops checking the contrast of the displayed strings.
+pub fn unset_min_contrast() {
+    MIN_CONTRAST.store(0, Ordering::Relaxed);
+}
+
+pub(crate) fn min_contrast() -> Option<f64> {
+    match MIN_CONTRAST.load(Ordering::Relaxed) {
+        0 => None,
+        bits => Some(f64::from_bits(bits)),
+    }
+}
+
+/// Whether the colorblind-safe mode is on.
+static CVD_SAFE: AtomicBool = AtomicBool::new(false);
+
+/// Turns the colorblind-safe mode on or off. When it is on, the standard colors are displayed
+/// as the ones of the Okabe-Ito palette, which people with a color vision deficiency can tell
+/// apart. See `cvd::safe_color`. They are written as RGB colors when `COLORTERM` is
+/// `truecolor` or `24bit`, as the closest palette indexes otherwise.
+pub fn set_cvd_safe(cvd_safe: bool) {
+    CVD_SAFE.store(cvd_safe, Ordering::Relaxed);
+}
+
+pub fn cvd_safe() -> bool {
+    CVD_SAFE.load(Ordering::Relaxed)
+}
+
 #[cfg(test)]
 mod specs {
     use super::*;
@@ -262,7 +381,7 @@ mod specs {
                         clicolor: Some(false),
                         ..ShouldColorize::default()
                     };
-                    false == colorize_control.should_colorize()
+                    !colorize_control.should_colorize()
                 });
 
                 ctx.it("clicolor == true means colors !", || {
@@ -270,11 +389,11 @@ mod specs {
                         clicolor: Some(true),
                         ..ShouldColorize::default()
                     };
-                    true == colorize_control.should_colorize()
+                    colorize_control.should_colorize()
                 });
 
                 ctx.it("unset clicolors implies true", || {
-                    true == ShouldColorize::default().should_colorize()
+                    ShouldColorize::default().should_colorize()
                 });
             });
 
@@ -288,7 +407,7 @@ mod specs {
                             ..ShouldColorize::default()
                         };
 
-                        true == colorize_control.should_colorize()
+                        colorize_control.should_colorize()
                     },
                 );
 
@@ -301,7 +420,7 @@ mod specs {
                             ..ShouldColorize::default()
                         };
 
-                        false == colorize_control.should_colorize()
+                        !colorize_control.should_colorize()
                     },
                 );
             });
@@ -313,10 +432,9 @@ mod specs {
                         clicolor_force: None,
                         has_manual_override: AtomicBool::new(true),
                         manual_override: AtomicBool::new(true),
-                        .. ShouldColorize::default()
                     };
 
-                    true == colorize_control.should_colorize()
+                    colorize_control.should_colorize()
                 });
 
                 ctx.it("should not colorize if manual_override is false, but clicolor is true or clicolor_force is true", || {
@@ -325,10 +443,9 @@ mod specs {
                         clicolor_force: Some(true),
                         has_manual_override: AtomicBool::new(true),
                         manual_override: AtomicBool::new(false),
-                        .. ShouldColorize::default()
                     };
 
-                    false == colorize_control.should_colorize()
+                    !colorize_control.should_colorize()
                 })
             });
 
@@ -342,21 +459,15 @@ mod specs {
                     let colorize_control = ShouldColorize::default();
                     colorize_control.set_override(true);
                     {
-                        assert_eq!(
-                            true,
-                            colorize_control.has_manual_override.load(Ordering::Relaxed)
-                        );
+                        assert!(colorize_control.has_manual_override.load(Ordering::Relaxed));
                         let val = colorize_control.manual_override.load(Ordering::Relaxed);
-                        assert_eq!(true, val);
+                        assert!(val);
                     }
                     colorize_control.set_override(false);
                     {
-                        assert_eq!(
-                            true,
-                            colorize_control.has_manual_override.load(Ordering::Relaxed)
-                        );
+                        assert!(colorize_control.has_manual_override.load(Ordering::Relaxed));
                         let val = colorize_control.manual_override.load(Ordering::Relaxed);
-                        assert_eq!(false, val);
+                        assert!(!val);
                     }
                 });
             });
@@ -371,10 +482,67 @@ mod specs {
                     let colorize_control = ShouldColorize::default();
                     colorize_control.set_override(true);
                     colorize_control.unset_override();
-                    assert_eq!(
-                        false,
-                        colorize_control.has_manual_override.load(Ordering::Relaxed)
-                    );
+                    assert!(!colorize_control.has_manual_override.load(Ordering::Relaxed));
+                });
+            });
+        });
+        runner.add_event_handler(&mut formatter);
+        runner.run().unwrap();
+    }
+
+    #[test]
+    fn hyperlink_behavior() {
+        use std::io;
+
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|&&(var, _)| var == name)
+                    .map(|&(_, value)| String::from(value))
+            }
+        };
+
+        let stdout = &mut io::stdout();
+        let mut formatter = rspec::formatter::Simple::new(stdout);
+        let mut runner = describe("ShouldHyperlink", |ctx| {
+            ctx.describe("::detect", |ctx| {
+                ctx.it("should not detect unknown terminals", || {
+                    assert!(!ShouldHyperlink::detect(env(&[])));
+                    assert!(!ShouldHyperlink::detect(env(&[("TERM", "xterm-256color")])));
+                    assert!(!ShouldHyperlink::detect(env(&[("VTE_VERSION", "4803")])));
+                });
+
+                ctx.it("should detect the known terminals", || {
+                    assert!(ShouldHyperlink::detect(env(&[("TERM_PROGRAM", "WezTerm")])));
+                    assert!(ShouldHyperlink::detect(env(&[("TERM", "xterm-kitty")])));
+                    assert!(ShouldHyperlink::detect(env(&[("VTE_VERSION", "6003")])));
+                    assert!(ShouldHyperlink::detect(env(&[("WT_SESSION", "1")])));
+                });
+
+                ctx.it("should obey FORCE_HYPERLINK, and be off on CI", || {
+                    assert!(ShouldHyperlink::detect(env(&[("FORCE_HYPERLINK", "1")])));
+                    assert!(!ShouldHyperlink::detect(env(&[
+                        ("FORCE_HYPERLINK", "0"),
+                        ("TERM", "xterm-kitty"),
+                    ])));
+                    assert!(!ShouldHyperlink::detect(env(&[
+                        ("CI", "true"),
+                        ("TERM", "xterm-kitty"),
+                    ])));
+                });
+            });
+
+            ctx.describe("::set_override", |ctx| {
+                ctx.it("should take precedence over the detection", || {
+                    let hyperlink_control = ShouldHyperlink {
+                        detected: true,
+                        has_manual_override: AtomicBool::new(false),
+                        manual_override: AtomicBool::new(false),
+                    };
+                    hyperlink_control.set_override(false);
+                    assert!(!hyperlink_control.should_hyperlink());
+                    hyperlink_control.unset_override();
+                    assert!(hyperlink_control.should_hyperlink());
                 });
             });
         });
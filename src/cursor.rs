@@ -0,0 +1,198 @@
+//! Escape sequences moving the cursor and clearing the screen.
+//!
+//! They are displayed like `ColoredString`s, and follow the same decision: nothing is written
+//! when colorization is disabled (see `control::SHOULD_COLORIZE`), so that the output piped to
+//! a file holds no escape sequence at all.
+//!
+//! ```
+//! use colored::*;
+//! use colored::cursor;
+//!
+//! print!("{}downloading...", cursor::hide());
+//! print!("{}{}{}", cursor::to_column(0), cursor::clear_line(), "done".green());
+//! println!("{}", cursor::show());
+//! ```
+
+use std::fmt;
+
+/// A cursor or screen command, written when displayed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    Up(u16),
+    Down(u16),
+    Forward(u16),
+    Back(u16),
+    /// To a column of the current line, from 0.
+    ToColumn(u16),
+    /// To a line and a column, from 0.
+    MoveTo(u16, u16),
+    SavePosition,
+    RestorePosition,
+    /// Clears the whole current line, the cursor staying where it is.
+    ClearLine,
+    /// Clears from the cursor to the end of the line.
+    ClearToEndOfLine,
+    /// Clears the whole screen, the cursor staying where it is.
+    ClearScreen,
+    /// Clears from the cursor to the end of the screen.
+    ClearToEndOfScreen,
+    Hide,
+    Show,
+    EnterAlternateScreen,
+    LeaveAlternateScreen,
+}
+
+impl Command {
+    /// The escape sequence of the command, whether colorization is enabled or not. The moves
+    /// by 0 have none, terminals moving by 1 in that case.
+    pub fn to_escape(self) -> String {
+        match self {
+            Command::Up(0) | Command::Down(0) | Command::Forward(0) | Command::Back(0) => {
+                String::new()
+            }
+            Command::Up(n) => format!("\x1B[{}A", n),
+            Command::Down(n) => format!("\x1B[{}B", n),
+            Command::Forward(n) => format!("\x1B[{}C", n),
+            Command::Back(n) => format!("\x1B[{}D", n),
+            Command::ToColumn(col) => format!("\x1B[{}G", u32::from(col) + 1),
+            Command::MoveTo(row, col) => {
+                format!("\x1B[{};{}H", u32::from(row) + 1, u32::from(col) + 1)
+            }
+            Command::SavePosition => String::from("\x1B7"),
+            Command::RestorePosition => String::from("\x1B8"),
+            Command::ClearLine => String::from("\x1B[2K"),
+            Command::ClearToEndOfLine => String::from("\x1B[K"),
+            Command::ClearScreen => String::from("\x1B[2J"),
+            Command::ClearToEndOfScreen => String::from("\x1B[J"),
+            Command::Hide => String::from("\x1B[?25l"),
+            Command::Show => String::from("\x1B[?25h"),
+            Command::EnterAlternateScreen => String::from("\x1B[?1049h"),
+            Command::LeaveAlternateScreen => String::from("\x1B[?1049l"),
+        }
+    }
+
+    #[cfg(not(feature = "no-color"))]
+    fn enabled() -> bool {
+        use control;
+
+        control::SHOULD_COLORIZE.should_colorize()
+    }
+
+    #[cfg(feature = "no-color")]
+    fn enabled() -> bool {
+        false
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if Command::enabled() {
+            f.write_str(&self.to_escape())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+pub fn up(lines: u16) -> Command {
+    Command::Up(lines)
+}
+
+pub fn down(lines: u16) -> Command {
+    Command::Down(lines)
+}
+
+pub fn forward(columns: u16) -> Command {
+    Command::Forward(columns)
+}
+
+pub fn back(columns: u16) -> Command {
+    Command::Back(columns)
+}
+
+/// Moves to a column of the current line, from 0.
+pub fn to_column(col: u16) -> Command {
+    Command::ToColumn(col)
+}
+
+/// Moves to a line and a column of the screen, from 0.
+pub fn move_to(row: u16, col: u16) -> Command {
+    Command::MoveTo(row, col)
+}
+
+pub fn save_position() -> Command {
+    Command::SavePosition
+}
+
+pub fn restore_position() -> Command {
+    Command::RestorePosition
+}
+
+pub fn clear_line() -> Command {
+    Command::ClearLine
+}
+
+pub fn clear_to_end_of_line() -> Command {
+    Command::ClearToEndOfLine
+}
+
+pub fn clear_screen() -> Command {
+    Command::ClearScreen
+}
+
+pub fn clear_to_end_of_screen() -> Command {
+    Command::ClearToEndOfScreen
+}
+
+pub fn hide() -> Command {
+    Command::Hide
+}
+
+pub fn show() -> Command {
+    Command::Show
+}
+
+/// Switches to the alternate screen, the one of full-screen applications, which is left with
+/// the main screen and the cursor as they were.
+pub fn enter_alternate_screen() -> Command {
+    Command::EnterAlternateScreen
+}
+
+pub fn leave_alternate_screen() -> Command {
+    Command::LeaveAlternateScreen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes() {
+        assert_eq!("\x1B[3A", up(3).to_escape());
+        assert_eq!("", down(0).to_escape());
+        assert_eq!("\x1B[1G", to_column(0).to_escape());
+        assert_eq!("\x1B[2;11H", move_to(1, 10).to_escape());
+        assert_eq!("\x1B[65536G", to_column(u16::MAX).to_escape());
+        assert_eq!("\x1B[?1049h", enter_alternate_screen().to_escape());
+    }
+
+    #[cfg(feature = "screen")]
+    #[test]
+    fn on_a_screen() {
+        use testing::Screen;
+
+        let mut screen = Screen::new(10, 3);
+        for command in &[move_to(2, 4), save_position(), up(1), back(2), hide()] {
+            screen.feed(&command.to_escape());
+        }
+        assert_eq!((1, 2), screen.cursor());
+        assert!(!screen.cursor_visible());
+
+        screen.feed("abc");
+        screen.feed(&to_column(3).to_escape());
+        screen.feed(&clear_to_end_of_line().to_escape());
+        screen.feed(&restore_position().to_escape());
+        assert_eq!("\n  a", screen.contents());
+        assert_eq!((2, 4), screen.cursor());
+    }
+}
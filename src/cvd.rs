@@ -0,0 +1,261 @@
+//! Color vision deficiencies: what colors look like to colorblind people, and colors they can
+//! tell apart.
+//!
+//! About one man in twelve can hardly tell red from green. `control::set_cvd_safe(true)` swaps
+//! the standard colors for the ones of the Okabe-Ito palette when displaying, so that
+//! `.red()` and `.green()` stay distinct:
+//!
+//! ```
+//! use colored::*;
+//! use colored::cvd::Deficiency;
+//!
+//! let red = TrueColor::new(255, 0, 0);
+//! println!("{} looks like {}", red, red.simulate(Deficiency::Deuteranopia));
+//!
+//! control::set_cvd_safe(true);
+//! println!("{} {}", "passed".green(), "failed".red());
+//! # control::set_cvd_safe(false);
+//! ```
+
+use color::{to_linear, AllColor, Color, TrueColor};
+
+/// The kinds of color blindness, where one of the three types of cones is missing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Deficiency {
+    /// No red cones.
+    Protanopia,
+    /// No green cones, the most common one.
+    Deuteranopia,
+    /// No blue cones.
+    Tritanopia,
+}
+
+impl Deficiency {
+    /// The transformation of the linear RGB channels, from Machado, Oliveira and Fernandes
+    /// (2009), at full severity.
+    fn matrix(self) -> [[f64; 3]; 3] {
+        match self {
+            Deficiency::Protanopia => [
+                [0.152_286, 1.052_583, -0.204_868],
+                [0.114_503, 0.786_281, 0.099_216],
+                [-0.003_882, -0.048_116, 1.051_998],
+            ],
+            Deficiency::Deuteranopia => [
+                [0.367_322, 0.860_646, -0.227_968],
+                [0.280_085, 0.672_501, 0.047_413],
+                [-0.011_820, 0.042_940, 0.968_881],
+            ],
+            Deficiency::Tritanopia => [
+                [1.255_528, -0.076_749, -0.178_779],
+                [-0.078_411, 0.930_809, 0.147_602],
+                [0.004_733, 0.691_367, 0.303_900],
+            ],
+        }
+    }
+}
+
+pub const BLACK: TrueColor = TrueColor::new(0x00, 0x00, 0x00);
+pub const ORANGE: TrueColor = TrueColor::new(0xe6, 0x9f, 0x00);
+pub const SKY_BLUE: TrueColor = TrueColor::new(0x56, 0xb4, 0xe9);
+pub const BLUISH_GREEN: TrueColor = TrueColor::new(0x00, 0x9e, 0x73);
+pub const YELLOW: TrueColor = TrueColor::new(0xf0, 0xe4, 0x42);
+pub const BLUE: TrueColor = TrueColor::new(0x00, 0x72, 0xb2);
+pub const VERMILLION: TrueColor = TrueColor::new(0xd5, 0x5e, 0x00);
+pub const REDDISH_PURPLE: TrueColor = TrueColor::new(0xcc, 0x79, 0xa7);
+
+/// The palette of Okabe and Ito, eight colors distinct for all kinds of color vision.
+pub const OKABE_ITO: [TrueColor; 8] = [
+    BLACK,
+    ORANGE,
+    SKY_BLUE,
+    BLUISH_GREEN,
+    YELLOW,
+    BLUE,
+    VERMILLION,
+    REDDISH_PURPLE,
+];
+
+fn from_linear(c: f64) -> u8 {
+    let c = c.clamp(0.0, 1.0);
+    let encoded = if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (encoded * 255.0).round() as u8
+}
+
+impl TrueColor {
+    /// What the color looks like to someone with the given color vision deficiency.
+    ///
+    /// # Example
+    /// ```
+    /// # use colored::*;
+    /// use colored::cvd::Deficiency;
+    ///
+    /// // red and green both become yellowish
+    /// let red = TrueColor::new(205, 0, 0).simulate(Deficiency::Deuteranopia);
+    /// let green = TrueColor::new(0, 205, 0).simulate(Deficiency::Deuteranopia);
+    /// assert_eq!(TrueColor::new(130, 115, 0), red);
+    /// assert_eq!(TrueColor::new(192, 172, 45), green);
+    /// ```
+    pub fn simulate(self, deficiency: Deficiency) -> TrueColor {
+        let rgb = [
+            to_linear(self.r()),
+            to_linear(self.g()),
+            to_linear(self.b()),
+        ];
+        let channel =
+            |row: [f64; 3]| from_linear(row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2]);
+        let [r, g, b] = deficiency.matrix();
+        TrueColor::new(channel(r), channel(g), channel(b))
+    }
+}
+
+/// The Okabe-Ito color standing for a standard color, for the colors which have one.
+fn safe_standard(color: Color) -> Option<TrueColor> {
+    match color {
+        Color::Red | Color::BrightRed => Some(VERMILLION),
+        Color::Green | Color::BrightGreen => Some(BLUISH_GREEN),
+        Color::Yellow => Some(ORANGE),
+        Color::BrightYellow => Some(YELLOW),
+        Color::Blue | Color::BrightBlue => Some(BLUE),
+        Color::Magenta | Color::BrightMagenta => Some(REDDISH_PURPLE),
+        Color::Cyan | Color::BrightCyan => Some(SKY_BLUE),
+        Color::Black | Color::White | Color::BrightBlack | Color::BrightWhite => None,
+    }
+}
+
+/// The color displayed in the colorblind-safe mode: the standard colors and the matching
+/// palette indexes are replaced by the Okabe-Ito ones, yellow by orange. Black, white, grays
+/// and RGB colors are kept.
+pub fn safe_color(color: AllColor) -> AllColor {
+    let standard = match color {
+        AllColor::Color(color) => Some(color),
+        AllColor::Palette(index) => Color::from_palette_index(index),
+        AllColor::True(_) => None,
+    };
+    standard
+        .and_then(safe_standard)
+        .map_or(color, AllColor::True)
+}
+
+/// Whether `COLORTERM` tells that the terminal displays RGB colors.
+fn is_truecolor(colorterm: Option<&str>) -> bool {
+    colorterm.map_or(false, |value| value == "truecolor" || value == "24bit")
+}
+
+/// The color displayed for `color`: the Okabe-Ito colors are RGB, written as the closest
+/// xterm palette index when the terminal may not display RGB colors.
+fn shown(color: AllColor, cvd_safe: bool, truecolor: bool) -> AllColor {
+    use palette::Palette;
+
+    if !cvd_safe {
+        return color;
+    }
+    let safe = safe_color(color);
+    match safe {
+        AllColor::True(rgb) if !truecolor && safe != color => {
+            AllColor::Palette(Palette::xterm().nearest_index(rgb))
+        }
+        _ => safe,
+    }
+}
+
+/// The color to display, depending on `control::set_cvd_safe` and `COLORTERM`.
+pub(crate) fn displayed(color: Option<AllColor>) -> Option<AllColor> {
+    use control;
+    use std::env;
+
+    lazy_static! {
+        static ref TRUECOLOR: bool = is_truecolor(env::var("COLORTERM").ok().as_deref());
+    }
+
+    color.map(|color| shown(color, control::cvd_safe(), *TRUECOLOR))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Deficiency; 3] = [
+        Deficiency::Protanopia,
+        Deficiency::Deuteranopia,
+        Deficiency::Tritanopia,
+    ];
+
+    #[test]
+    fn grays_unchanged() {
+        for &deficiency in &ALL {
+            for &gray in &[0, 128, 255] {
+                let color = TrueColor::new(gray, gray, gray).simulate(deficiency);
+                for &channel in &[color.r(), color.g(), color.b()] {
+                    assert!((i16::from(channel) - i16::from(gray)).abs() <= 1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn red_and_green_confused() {
+        let red = TrueColor::new(0xcd, 0, 0);
+        let green = TrueColor::new(0, 0xcd, 0);
+
+        for &deficiency in &[Deficiency::Protanopia, Deficiency::Deuteranopia] {
+            // both become yellowish, the blue channel being all that tells hues apart
+            assert!(red.simulate(deficiency).b() < 50);
+            assert!(green.simulate(deficiency).b() < 50);
+            // while these stay yellowish and bluish
+            assert!(VERMILLION.simulate(deficiency).b() < 50);
+            assert!(BLUISH_GREEN.simulate(deficiency).b() > 100);
+        }
+        // the blue cones don't matter much there
+        assert!(red.simulate(Deficiency::Tritanopia).r() > 150);
+    }
+
+    #[test]
+    fn safe_colors() {
+        assert_eq!(
+            AllColor::True(VERMILLION),
+            safe_color(AllColor::Color(Color::Red))
+        );
+        assert_eq!(
+            AllColor::True(BLUISH_GREEN),
+            safe_color(AllColor::Palette(10))
+        );
+        assert_eq!(
+            AllColor::Color(Color::White),
+            safe_color(AllColor::Color(Color::White))
+        );
+        assert_eq!(AllColor::Palette(208), safe_color(AllColor::Palette(208)));
+        assert_eq!(
+            AllColor::True(TrueColor::new(1, 2, 3)),
+            safe_color(AllColor::True(TrueColor::new(1, 2, 3)))
+        );
+    }
+
+    #[test]
+    fn shown_colors() {
+        use palette::Palette;
+
+        let red = AllColor::Color(Color::Red);
+        assert_eq!(red, shown(red, false, false));
+        assert_eq!(AllColor::True(VERMILLION), shown(red, true, true));
+        // 166 is #d75f00
+        assert_eq!(AllColor::Palette(166), shown(red, true, false));
+        // the RGB colors are written as asked, the terminal supporting them or not
+        let rgb = AllColor::True(TrueColor::new(1, 2, 3));
+        assert_eq!(rgb, shown(rgb, true, false));
+
+        // the palette indexes are the fixed ones, not the 16 colors the safe ones replace
+        for &color in &OKABE_ITO[1..] {
+            let index = Palette::xterm().nearest_index(color);
+            assert!(index >= 16, "{} is shown as {}", color, index);
+        }
+
+        assert!(is_truecolor(Some("truecolor")));
+        assert!(is_truecolor(Some("24bit")));
+        assert!(!is_truecolor(Some("256")));
+        assert!(!is_truecolor(None));
+    }
+}
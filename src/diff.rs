@@ -0,0 +1,605 @@
+//! Colored unified diffs, with the changed words of the modified lines emphasized.
+//!
+//! ```
+//! use colored::diff::Diff;
+//!
+//! let old = "name = \"colored\"\nversion = \"1.8.0\"\n";
+//! let new = "name = \"colored\"\nversion = \"1.9.0\"\n";
+//! println!("{}", Diff::new(old, new).labels("Cargo.toml", "Cargo.toml"));
+//! ```
+//!
+//! Like everything else in this crate, the output is plain when colorization is disabled.
+
+use std::cmp;
+use std::fmt;
+
+use color::Color;
+use style::{ColoredStyle, Styles};
+
+/// The styles of a diff.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiffStyles {
+    /// The `---` and `+++` lines.
+    pub header: ColoredStyle,
+    /// The `@@ -1,3 +1,4 @@` lines.
+    pub hunk_header: ColoredStyle,
+    pub context: ColoredStyle,
+    pub removed: ColoredStyle,
+    pub added: ColoredStyle,
+    /// The changed words of a removed line.
+    pub removed_emphasis: ColoredStyle,
+    /// The changed words of an added line.
+    pub added_emphasis: ColoredStyle,
+}
+
+/// Red and green lines, the changed words being reversed.
+impl Default for DiffStyles {
+    fn default() -> Self {
+        DiffStyles {
+            header: ColoredStyle::new().bold(),
+            hunk_header: ColoredStyle::new().color(Color::Cyan),
+            context: ColoredStyle::new(),
+            removed: ColoredStyle::new().color(Color::Red),
+            added: ColoredStyle::new().color(Color::Green),
+            removed_emphasis: ColoredStyle::new().color(Color::Red).with(Styles::Reversed),
+            added_emphasis: ColoredStyle::new()
+                .color(Color::Green)
+                .with(Styles::Reversed),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// The edit script from `old` to `new`, deletions coming before insertions.
+///
+/// It is the shortest one, found with the linear space variant of the algorithm of Myers
+/// ("An O(ND) Difference Algorithm and Its Variations", 1986): the memory is linear in the
+/// length of the texts, and the time grows with the number of differences.
+fn diff_ops<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Op> {
+    let mut ops = Vec::with_capacity(cmp::max(old.len(), new.len()));
+    diff_range(old, new, (0, old.len()), (0, new.len()), &mut ops);
+
+    // the changes between two common lines: their deletions, then their insertions
+    let mut start = 0;
+    while start < ops.len() {
+        let len = ops[start..]
+            .iter()
+            .take_while(|op| !matches!(op, Op::Equal(..)))
+            .count();
+        ops[start..start + len].sort_by_key(|op| match *op {
+            Op::Delete(_) => 0,
+            _ => 1,
+        });
+        start += len + 1;
+    }
+    ops
+}
+
+/// Appends the edit script from `old[x0..x1]` to `new[y0..y1]` to `ops`.
+fn diff_range<T: PartialEq>(
+    old: &[T],
+    new: &[T],
+    (mut x0, mut x1): (usize, usize),
+    (mut y0, mut y1): (usize, usize),
+    ops: &mut Vec<Op>,
+) {
+    while x0 < x1 && y0 < y1 && old[x0] == new[y0] {
+        ops.push(Op::Equal(x0, y0));
+        x0 += 1;
+        y0 += 1;
+    }
+    let mut suffix = 0;
+    while x0 < x1 && y0 < y1 && old[x1 - 1] == new[y1 - 1] {
+        x1 -= 1;
+        y1 -= 1;
+        suffix += 1;
+    }
+
+    if x0 == x1 {
+        ops.extend((y0..y1).map(Op::Insert));
+    } else if y0 == y1 {
+        ops.extend((x0..x1).map(Op::Delete));
+    } else {
+        let ((x, y), (u, v)) = middle_snake(&old[x0..x1], &new[y0..y1]);
+        diff_range(old, new, (x0, x0 + x), (y0, y0 + y), ops);
+        ops.extend((0..u - x).map(|k| Op::Equal(x0 + x + k, y0 + y + k)));
+        diff_range(old, new, (x0 + u, x1), (y0 + v, y1), ops);
+    }
+    ops.extend((0..suffix).map(|k| Op::Equal(x1 + k, y1 + k)));
+}
+
+/// The start and the end of the run of common elements in the middle of a shortest edit script
+/// from `a` to `b`, found by looking for it from both ends at once. `a` and `b` are not empty.
+fn middle_snake<T: PartialEq>(a: &[T], b: &[T]) -> ((usize, usize), (usize, usize)) {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let delta = n - m;
+    let odd = delta % 2 != 0;
+    let max = (n + m + 1) / 2;
+    // the furthest `x` reached on each diagonal `k = x - y`, from the start and from the end
+    let offset = max + 1;
+    let mut forward = vec![0; 2 * offset as usize + 1];
+    let mut backward = vec![0; 2 * offset as usize + 1];
+    let at = |k: isize| (k + offset) as usize;
+
+    for d in 0..=max {
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && forward[at(k - 1)] < forward[at(k + 1)]) {
+                forward[at(k + 1)]
+            } else {
+                forward[at(k - 1)] + 1
+            };
+            let mut y = x - k;
+            let start = (x, y);
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            forward[at(k)] = x;
+            if odd && (delta - k).abs() < d && x + backward[at(delta - k)] >= n {
+                return (
+                    (start.0 as usize, start.1 as usize),
+                    (x as usize, y as usize),
+                );
+            }
+        }
+
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && backward[at(k - 1)] < backward[at(k + 1)]) {
+                backward[at(k + 1)]
+            } else {
+                backward[at(k - 1)] + 1
+            };
+            let mut y = x - k;
+            let end = (n - x, m - y);
+            while x < n && y < m && a[(n - x - 1) as usize] == b[(m - y - 1) as usize] {
+                x += 1;
+                y += 1;
+            }
+            backward[at(k)] = x;
+            if !odd && (delta - k).abs() <= d && x + forward[at(delta - k)] >= n {
+                return (
+                    ((n - x) as usize, (m - y) as usize),
+                    (end.0 as usize, end.1 as usize),
+                );
+            }
+        }
+    }
+    unreachable!("the paths from both ends always meet")
+}
+
+/// Words, runs of spaces, and single punctuation characters.
+fn words(line: &str) -> Vec<&str> {
+    let kind = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            0
+        } else if c.is_whitespace() {
+            1
+        } else {
+            2
+        }
+    };
+
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut previous = None;
+    for (idx, c) in line.char_indices() {
+        let current = kind(c);
+        if idx > start && (previous != Some(current) || current == 2) {
+            words.push(&line[start..idx]);
+            start = idx;
+        }
+        previous = Some(current);
+    }
+    if start < line.len() {
+        words.push(&line[start..]);
+    }
+    words
+}
+
+/// The changed part of two lines, as byte ranges, when they have a common start or end.
+fn changed_ranges(old: &str, new: &str) -> Option<((usize, usize), (usize, usize))> {
+    let (old_words, new_words) = (words(old), words(new));
+    let prefix = old_words
+        .iter()
+        .zip(&new_words)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_words[prefix..]
+        .iter()
+        .rev()
+        .zip(new_words[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    if prefix + suffix == 0 {
+        return None;
+    }
+    let range = |words: &[&str], line: &str| {
+        let start: usize = words[..prefix].iter().map(|word| word.len()).sum();
+        let end: usize = line.len()
+            - words[words.len() - suffix..]
+                .iter()
+                .map(|word| word.len())
+                .sum::<usize>();
+        (start, end)
+    };
+    Some((range(&old_words, old), range(&new_words, new)))
+}
+
+/// A unified diff between two texts, rendered by its `Display` implementation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diff<'a> {
+    old: &'a str,
+    new: &'a str,
+    labels: Option<(String, String)>,
+    context: usize,
+    styles: DiffStyles,
+}
+
+impl<'a> Diff<'a> {
+    /// The diff from `old` to `new`, with 3 lines of context.
+    pub fn new(old: &'a str, new: &'a str) -> Diff<'a> {
+        Diff {
+            old,
+            new,
+            labels: None,
+            context: 3,
+            styles: DiffStyles::default(),
+        }
+    }
+
+    /// The names of the texts, for the `---` and `+++` lines.
+    pub fn labels(self, old: &str, new: &str) -> Diff<'a> {
+        Diff {
+            labels: Some((String::from(old), String::from(new))),
+            ..self
+        }
+    }
+
+    /// The number of unchanged lines shown around the changes.
+    pub fn context(self, context: usize) -> Diff<'a> {
+        Diff { context, ..self }
+    }
+
+    pub fn styles(self, styles: DiffStyles) -> Diff<'a> {
+        Diff { styles, ..self }
+    }
+
+    /// Whether the texts have the same lines.
+    pub fn is_empty(&self) -> bool {
+        self.old.lines().eq(self.new.lines())
+    }
+
+    /// The lines of the diff, empty when the texts are the same.
+    pub fn lines(&self) -> Vec<String> {
+        let old: Vec<&str> = self.old.lines().collect();
+        let new: Vec<&str> = self.new.lines().collect();
+        let ops = diff_ops(&old, &new);
+        let styles = &self.styles;
+        let mut lines = Vec::new();
+
+        // the ranges of operations shown, changes and their context
+        let mut hunks: Vec<(usize, usize)> = Vec::new();
+        for (idx, op) in ops.iter().enumerate() {
+            if let Op::Equal(..) = *op {
+                continue;
+            }
+            let start = idx.saturating_sub(self.context);
+            let end = cmp::min(ops.len(), idx + 1 + self.context);
+            match hunks.last_mut() {
+                Some(last) if start <= last.1 => last.1 = end,
+                _ => hunks.push((start, end)),
+            }
+        }
+        if hunks.is_empty() {
+            return lines;
+        }
+
+        if let Some((ref old_label, ref new_label)) = self.labels {
+            lines.push(
+                styles
+                    .header
+                    .apply(&format!("--- {}", old_label))
+                    .to_string(),
+            );
+            lines.push(
+                styles
+                    .header
+                    .apply(&format!("+++ {}", new_label))
+                    .to_string(),
+            );
+        }
+
+        for (start, end) in hunks {
+            let hunk = &ops[start..end];
+            let old_start = ops[..start]
+                .iter()
+                .filter(|op| !matches!(op, Op::Insert(_)))
+                .count();
+            let new_start = ops[..start]
+                .iter()
+                .filter(|op| !matches!(op, Op::Delete(_)))
+                .count();
+            let old_len = hunk
+                .iter()
+                .filter(|op| !matches!(op, Op::Insert(_)))
+                .count();
+            let new_len = hunk
+                .iter()
+                .filter(|op| !matches!(op, Op::Delete(_)))
+                .count();
+            // an empty range starts at the line before it
+            let position = |start: usize, len: usize| {
+                format!("{},{}", if len == 0 { start } else { start + 1 }, len)
+            };
+            let header = format!(
+                "@@ -{} +{} @@",
+                position(old_start, old_len),
+                position(new_start, new_len)
+            );
+            lines.push(styles.hunk_header.apply(&header).to_string());
+
+            let mut idx = 0;
+            while idx < hunk.len() {
+                if let Op::Equal(i, _) = hunk[idx] {
+                    lines.push(styles.context.apply(&format!(" {}", old[i])).to_string());
+                    idx += 1;
+                    continue;
+                }
+
+                // a block of changes: deletions, then insertions
+                let deleted: Vec<usize> = hunk[idx..]
+                    .iter()
+                    .take_while(|op| matches!(op, Op::Delete(_)))
+                    .map(|op| match *op {
+                        Op::Delete(i) => i,
+                        _ => unreachable!(),
+                    })
+                    .collect();
+                idx += deleted.len();
+                let inserted: Vec<usize> = hunk[idx..]
+                    .iter()
+                    .take_while(|op| matches!(op, Op::Insert(_)))
+                    .map(|op| match *op {
+                        Op::Insert(j) => j,
+                        _ => unreachable!(),
+                    })
+                    .collect();
+                idx += inserted.len();
+
+                // the lines changed in place are paired, to emphasize their differences
+                let ranges: Vec<_> = deleted
+                    .iter()
+                    .zip(&inserted)
+                    .map(|(&i, &j)| changed_ranges(old[i], new[j]))
+                    .collect();
+                for (k, &i) in deleted.iter().enumerate() {
+                    let range = ranges.get(k).cloned().unwrap_or(None).map(|r| r.0);
+                    lines.push(self.changed_line('-', old[i], range, true));
+                }
+                for (k, &j) in inserted.iter().enumerate() {
+                    let range = ranges.get(k).cloned().unwrap_or(None).map(|r| r.1);
+                    lines.push(self.changed_line('+', new[j], range, false));
+                }
+            }
+        }
+        lines
+    }
+
+    fn changed_line(
+        &self,
+        sign: char,
+        line: &str,
+        changed: Option<(usize, usize)>,
+        removed: bool,
+    ) -> String {
+        let (style, emphasis) = if removed {
+            (self.styles.removed, self.styles.removed_emphasis)
+        } else {
+            (self.styles.added, self.styles.added_emphasis)
+        };
+
+        match changed {
+            Some((start, end)) if start < end => format!(
+                "{}{}{}",
+                style.apply(&format!("{}{}", sign, &line[..start])),
+                emphasis.apply(&line[start..end]),
+                style.apply(&line[end..])
+            ),
+            _ => style.apply(&format!("{}{}", sign, line)).to_string(),
+        }
+    }
+}
+
+impl<'a> fmt::Display for Diff<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.lines().join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use width::strip_escapes;
+    use Colorize;
+
+    fn plain(diff: &Diff) -> String {
+        strip_escapes(&diff.to_string())
+    }
+
+    #[test]
+    fn edit_script() {
+        assert_eq!(
+            vec![
+                Op::Equal(0, 0),
+                Op::Delete(1),
+                Op::Insert(1),
+                Op::Equal(2, 2),
+                Op::Insert(3),
+            ],
+            diff_ops(&["a", "b", "c"], &["a", "x", "c", "d"])
+        );
+        assert_eq!(Vec::<Op>::new(), diff_ops::<&str>(&[], &[]));
+    }
+
+    /// The length of the longest common subsequence, the number of `Equal` of the shortest
+    /// edit scripts.
+    fn lcs_len(a: &[u8], b: &[u8]) -> usize {
+        let mut lcs = vec![vec![0; b.len() + 1]; a.len() + 1];
+        for i in (0..a.len()).rev() {
+            for j in (0..b.len()).rev() {
+                lcs[i][j] = if a[i] == b[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    cmp::max(lcs[i + 1][j], lcs[i][j + 1])
+                };
+            }
+        }
+        lcs[0][0]
+    }
+
+    #[test]
+    fn shortest_edit_scripts() {
+        // a small linear congruential generator, for texts made of few different lines
+        let mut seed: u32 = 12345;
+        let mut text = |len: usize| -> Vec<u8> {
+            (0..len)
+                .map(|_| {
+                    seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                    (seed >> 16) as u8 % 4
+                })
+                .collect()
+        };
+
+        for round in 0..300 {
+            let old = text(round % 23);
+            let new = text(round % 17);
+            let ops = diff_ops(&old, &new);
+
+            let (mut i, mut j, mut equal) = (0, 0, 0);
+            let mut inserting = false;
+            for op in &ops {
+                match *op {
+                    Op::Equal(x, y) => {
+                        assert_eq!((i, j), (x, y));
+                        assert_eq!(old[x], new[y]);
+                        i += 1;
+                        j += 1;
+                        equal += 1;
+                        inserting = false;
+                    }
+                    Op::Delete(x) => {
+                        assert!(!inserting, "{:?}", ops);
+                        assert_eq!(i, x);
+                        i += 1;
+                    }
+                    Op::Insert(y) => {
+                        assert_eq!(j, y);
+                        j += 1;
+                        inserting = true;
+                    }
+                }
+            }
+            assert_eq!((old.len(), new.len()), (i, j));
+            assert_eq!(lcs_len(&old, &new), equal, "{:?} {:?}", old, new);
+        }
+    }
+
+    #[test]
+    fn long_texts() {
+        let old: Vec<usize> = (0..20_000).collect();
+        let new: Vec<usize> = (0..20_000).filter(|i| i % 1000 != 0).collect();
+        let ops = diff_ops(&old, &new);
+        assert_eq!(
+            20,
+            ops.iter().filter(|op| matches!(op, Op::Delete(_))).count()
+        );
+        assert_eq!(20_000, ops.len());
+    }
+
+    #[test]
+    fn unified_format() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n";
+        let new = "1\n2\n3\nfour\n5\n6\n7\n8\n9\n10\n11\n";
+
+        assert_eq!(
+            "--- old\n+++ new\n\
+             @@ -3,3 +3,3 @@\n 3\n-4\n+four\n 5\n\
+             @@ -10,1 +10,2 @@\n 10\n+11",
+            plain(&Diff::new(old, new).labels("old", "new").context(1))
+        );
+        assert_eq!(
+            "@@ -1,10 +1,11 @@\n 1\n 2\n 3\n-4\n+four\n 5\n 6\n 7\n 8\n 9\n 10\n+11",
+            plain(&Diff::new(old, new))
+        );
+        assert_eq!("@@ -0,0 +1,1 @@\n+a", plain(&Diff::new("", "a\n")));
+        assert_eq!("@@ -1,1 +0,0 @@\n-a", plain(&Diff::new("a", "")));
+    }
+
+    #[test]
+    fn same_texts() {
+        let diff = Diff::new("a\nb", "a\nb");
+        assert!(diff.is_empty());
+        assert_eq!("", diff.to_string());
+    }
+
+    #[test]
+    fn colors() {
+        let lines = Diff::new("a\nb\n", "a\nc\n").lines();
+
+        assert_eq!(" a", lines[1]);
+        assert_eq!("-b".red().to_string(), lines[2]);
+        assert_eq!("+c".green().to_string(), lines[3]);
+        assert_eq!("@@ -1,2 +1,2 @@".cyan().to_string(), lines[0]);
+    }
+
+    #[test]
+    fn changed_words() {
+        let lines = Diff::new("version = \"1.8.0\"", "version = \"1.9.0\"").lines();
+
+        assert_eq!(
+            format!(
+                "{}{}{}",
+                "-version = \"1.".red(),
+                "8".red().reversed(),
+                ".0\"".red()
+            ),
+            lines[1]
+        );
+        assert_eq!(
+            format!(
+                "{}{}{}",
+                "+version = \"1.".green(),
+                "9".green().reversed(),
+                ".0\"".green()
+            ),
+            lines[2]
+        );
+        assert_eq!(
+            Some(((4, 9), (4, 7))),
+            changed_ranges("let value = 1;", "let foo = 1;")
+        );
+        assert_eq!(None, changed_ranges("abc", "def"));
+    }
+
+    #[test]
+    fn custom_styles() {
+        let styles = DiffStyles {
+            removed: ColoredStyle::new().color(Color::Magenta),
+            hunk_header: ColoredStyle::new(),
+            ..DiffStyles::default()
+        };
+        let diff = Diff::new("a", "b").styles(styles);
+
+        assert_eq!(
+            format!("@@ -1,1 +1,1 @@\n{}\n{}", "-a".magenta(), "+b".green()),
+            diff.to_string()
+        );
+    }
+}
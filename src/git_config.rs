@@ -0,0 +1,303 @@
+//! Parsing of git's color values, as found in the `color.*` settings of `git config`.
+//!
+//! ```
+//! use colored::*;
+//! use colored::git_config::GitColor;
+//!
+//! let color: GitColor = "bold red ul #000000".parse().unwrap();
+//! println!("{}", color.paint("error"));
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use color::{AllColor, Color};
+use style::{ColoredStyle, Styles};
+use {ColoredString, Colorize};
+
+/// The attributes git knows, with their names.
+const ATTRIBUTES: [(&str, Styles); 7] = [
+    ("bold", Styles::Bold),
+    ("dim", Styles::Dimmed),
+    ("italic", Styles::Italic),
+    ("ul", Styles::Underline),
+    ("blink", Styles::Blink),
+    ("reverse", Styles::Reversed),
+    ("strike", Styles::Strikethrough),
+];
+
+/// A git color value that couldn't be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GitColorError {
+    value: String,
+    reason: String,
+}
+
+impl fmt::Display for GitColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid git color {:?}: {}", self.value, self.reason)
+    }
+}
+
+impl Error for GitColorError {}
+
+/// A color setting, like `"bold red ul"` or `"normal blue nobold"`.
+///
+/// The first color is the foreground, the second one the background. `normal` keeps a color
+/// unchanged, and `default` uses the default color of the terminal. Attributes can be turned
+/// off with a `no` or `no-` prefix (`nobold`), and `reset` turns off everything before applying
+/// the rest.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GitColor {
+    style: ColoredStyle,
+    off: Vec<Styles>,
+    default_fg: bool,
+    default_bg: bool,
+    reset: bool,
+}
+
+impl GitColor {
+    /// The colors and attributes to turn on.
+    pub fn style(&self) -> ColoredStyle {
+        self.style
+    }
+
+    pub fn fgcolor(&self) -> Option<AllColor> {
+        self.style.fgcolor()
+    }
+
+    pub fn bgcolor(&self) -> Option<AllColor> {
+        self.style.bgcolor()
+    }
+
+    /// The attributes to turn off (`nobold`...).
+    pub fn off(&self) -> &[Styles] {
+        &self.off
+    }
+
+    pub fn is_default_fg(&self) -> bool {
+        self.default_fg
+    }
+
+    pub fn is_default_bg(&self) -> bool {
+        self.default_bg
+    }
+
+    pub fn is_reset(&self) -> bool {
+        self.reset
+    }
+
+    /// Paints `input` with this color. The attributes turned off are turned off even when
+    /// nested in a string which has them.
+    pub fn paint(&self, input: &str) -> ColoredString {
+        let mut colored = self.style.apply(input);
+
+        let off: Vec<Styles> = if self.reset {
+            ATTRIBUTES
+                .iter()
+                .map(|&(_, style)| style)
+                .filter(|&style| !self.style.has(style))
+                .collect()
+        } else {
+            self.off.clone()
+        };
+        for style in off {
+            colored = colored.remove_style(style);
+        }
+
+        if self.fgcolor().is_none() && (self.default_fg || self.reset) {
+            colored = colored.default_fg();
+        }
+        if self.bgcolor().is_none() && (self.default_bg || self.reset) {
+            colored = colored.default_bg();
+        }
+        colored
+    }
+}
+
+/// The colors of a git setting, `None` standing for `normal`.
+fn parse_color(word: &str) -> Result<Option<AllColor>, ()> {
+    if word == "normal" {
+        return Ok(None);
+    }
+
+    let color = match word {
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "white" => Color::White,
+        "brightblack" => Color::BrightBlack,
+        "brightred" => Color::BrightRed,
+        "brightgreen" => Color::BrightGreen,
+        "brightyellow" => Color::BrightYellow,
+        "brightblue" => Color::BrightBlue,
+        "brightmagenta" => Color::BrightMagenta,
+        "brightcyan" => Color::BrightCyan,
+        "brightwhite" => Color::BrightWhite,
+        _ if word.starts_with('#') => return word.parse().map(|c| Some(AllColor::True(c))),
+        _ => {
+            return word
+                .parse()
+                .map(|c| Some(AllColor::Palette(c)))
+                .map_err(|_| ())
+        }
+    };
+    Ok(Some(AllColor::Color(color)))
+}
+
+fn parse_attribute(word: &str) -> Option<Styles> {
+    ATTRIBUTES
+        .iter()
+        .find(|&&(name, _)| name == word)
+        .map(|&(_, style)| style)
+}
+
+impl FromStr for GitColor {
+    type Err = GitColorError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let error = |reason: String| GitColorError {
+            value: String::from(value),
+            reason,
+        };
+        let mut git_color = GitColor::default();
+        let mut colors = 0;
+
+        for word in value.split_whitespace() {
+            let lower = word.to_lowercase();
+            let word = lower.as_str();
+
+            // `Some(None)` for `normal` and `default`, which don't set any color
+            let color = match word {
+                "default" => Some(None),
+                _ => parse_color(word).ok(),
+            };
+
+            if word == "reset" {
+                git_color.reset = true;
+            } else if let Some(color) = color {
+                colors += 1;
+                match (colors, color) {
+                    (1, Some(color)) => git_color.style = git_color.style.fg(color),
+                    (1, None) => git_color.default_fg = word == "default",
+                    (2, Some(color)) => git_color.style = git_color.style.bg(color),
+                    (2, None) => git_color.default_bg = word == "default",
+                    _ => return Err(error(format!("too many colors, {:?} is the third", word))),
+                }
+            } else if let Some(style) = parse_attribute(word) {
+                git_color.style = git_color.style.with(style);
+                git_color.off.retain(|&off| off != style);
+            } else if let Some(style) = word
+                .strip_prefix("no-")
+                .or_else(|| word.strip_prefix("no"))
+                .and_then(parse_attribute)
+            {
+                git_color.style = git_color.style.without(style);
+                if !git_color.off.contains(&style) {
+                    git_color.off.push(style);
+                }
+            } else {
+                return Err(error(format!("unknown word {:?}", word)));
+            }
+        }
+
+        Ok(git_color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use TrueColor;
+
+    fn parse(value: &str) -> GitColor {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn colors() {
+        let color = parse("red blue");
+        assert_eq!(Some(AllColor::Color(Color::Red)), color.fgcolor());
+        assert_eq!(Some(AllColor::Color(Color::Blue)), color.bgcolor());
+
+        let color = parse("normal brightgreen");
+        assert_eq!(None, color.fgcolor());
+        assert_eq!(Some(AllColor::Color(Color::BrightGreen)), color.bgcolor());
+
+        let color = parse("#ff0000 208");
+        assert_eq!(
+            Some(AllColor::True(TrueColor::new(255, 0, 0))),
+            color.fgcolor()
+        );
+        assert_eq!(Some(AllColor::Palette(208)), color.bgcolor());
+
+        let color = parse("default normal");
+        assert!(color.is_default_fg());
+        assert!(!color.is_default_bg());
+    }
+
+    #[test]
+    fn attributes() {
+        let color = parse("bold red ul #ff0000");
+        assert_eq!(
+            ColoredStyle::new()
+                .bold()
+                .underline()
+                .color(Color::Red)
+                .on_true_color(255, 0, 0),
+            color.style()
+        );
+
+        let color = parse("reverse nobold no-italic");
+        assert!(color.style().has(Styles::Reversed));
+        assert_eq!(&[Styles::Bold, Styles::Italic], color.off());
+    }
+
+    #[test]
+    fn paint() {
+        assert_eq!("x".red().bold(), parse("red bold").paint("x"));
+        assert_eq!("x".red().not_bold(), parse("red nobold").paint("x"));
+        assert_eq!("x".default_fg().on_blue(), parse("default blue").paint("x"));
+        assert!(parse("").paint("x").is_plain());
+    }
+
+    #[test]
+    fn reset() {
+        let color = parse("reset bold");
+        let expected = "x"
+            .bold()
+            .not_italic()
+            .remove_style(Styles::Dimmed)
+            .not_underline()
+            .remove_style(Styles::Blink)
+            .remove_style(Styles::Reversed)
+            .remove_style(Styles::Strikethrough)
+            .default_fg()
+            .default_bg();
+
+        assert!(color.is_reset());
+        assert_eq!(expected, color.paint("x"));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            "invalid git color \"bold rde\": unknown word \"rde\"",
+            "bold rde".parse::<GitColor>().unwrap_err().to_string()
+        );
+        assert_eq!(
+            "invalid git color \"red blue green\": too many colors, \"green\" is the third",
+            "red blue green"
+                .parse::<GitColor>()
+                .unwrap_err()
+                .to_string()
+        );
+        assert!("256".parse::<GitColor>().is_err());
+        assert!("#ff00".parse::<GitColor>().is_err());
+    }
+}
@@ -0,0 +1,263 @@
+//! Parsing of the `GREP_COLORS` environment variable of GNU grep.
+//!
+//! ```
+//! use colored::grep_colors::GrepColors;
+//!
+//! let colors = GrepColors::parse("ms=01;32:fn=34").unwrap();
+//! if let Some(style) = colors.file_name() {
+//!     println!("{}: ...", style.apply("src/lib.rs"));
+//! }
+//! ```
+
+use std::env;
+use std::error::Error;
+use std::fmt;
+
+use style::ColoredStyle;
+
+/// The defaults of GNU grep.
+const GREP_DEFAULTS: &str = "ms=01;31:mc=01;31:sl=:cx=:fn=35:ln=32:bn=32:se=36";
+
+/// The keys of `GREP_COLORS` taking a style.
+const STYLE_KEYS: &[&str] = &["mt", "ms", "mc", "sl", "cx", "fn", "ln", "bn", "se"];
+
+/// A `GREP_COLORS` entry that couldn't be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GrepColorsError {
+    entry: String,
+    reason: String,
+}
+
+impl fmt::Display for GrepColorsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid GREP_COLORS entry {:?}: {}",
+            self.entry, self.reason
+        )
+    }
+}
+
+impl Error for GrepColorsError {}
+
+/// The styles of a `GREP_COLORS` specification. An empty value means no style at all.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GrepColors {
+    selected_match: Option<ColoredStyle>,
+    context_match: Option<ColoredStyle>,
+    selected_line: Option<ColoredStyle>,
+    context_line: Option<ColoredStyle>,
+    file_name: Option<ColoredStyle>,
+    line_number: Option<ColoredStyle>,
+    byte_offset: Option<ColoredStyle>,
+    separator: Option<ColoredStyle>,
+    reverse: bool,
+    no_erase: bool,
+}
+
+/// The GNU grep defaults.
+impl Default for GrepColors {
+    fn default() -> Self {
+        let mut colors = GrepColors {
+            selected_match: None,
+            context_match: None,
+            selected_line: None,
+            context_line: None,
+            file_name: None,
+            line_number: None,
+            byte_offset: None,
+            separator: None,
+            reverse: false,
+            no_erase: false,
+        };
+        colors
+            .extend(GREP_DEFAULTS)
+            .expect("the GNU grep defaults are valid");
+        colors
+    }
+}
+
+impl GrepColors {
+    /// Reads the `GREP_COLORS` environment variable, on top of the GNU grep defaults. An
+    /// invalid variable is ignored.
+    pub fn from_env() -> GrepColors {
+        env::var("GREP_COLORS")
+            .ok()
+            .and_then(|spec| GrepColors::parse(&spec).ok())
+            .unwrap_or_default()
+    }
+
+    /// Parses a `GREP_COLORS` specification (`"ms=01;31:mc=01;31:sl=:cx=:fn=35"`), on top of
+    /// the GNU grep defaults.
+    pub fn parse(spec: &str) -> Result<GrepColors, GrepColorsError> {
+        let mut colors = GrepColors::default();
+        colors.extend(spec)?;
+        Ok(colors)
+    }
+
+    /// Applies the entries of a `GREP_COLORS` specification to these ones. Unknown keys are
+    /// skipped, as GNU grep does, so that the ones of newer versions don't cancel the others.
+    pub fn extend(&mut self, spec: &str) -> Result<(), GrepColorsError> {
+        for entry in spec.split(':').filter(|entry| !entry.is_empty()) {
+            let error = |reason: String| GrepColorsError {
+                entry: String::from(entry),
+                reason,
+            };
+            let (key, value) = match entry.find('=') {
+                Some(idx) => (&entry[..idx], Some(&entry[idx + 1..])),
+                None => (entry, None),
+            };
+
+            if key != "rv" && key != "ne" && !STYLE_KEYS.contains(&key) {
+                continue;
+            }
+            match (key, value) {
+                ("rv", None) => self.reverse = true,
+                ("ne", None) => self.no_erase = true,
+                ("rv", Some(_)) | ("ne", Some(_)) => {
+                    return Err(error(format!("{:?} is a boolean and takes no value", key)))
+                }
+                (_, Some(value)) => {
+                    let style = if value.is_empty() {
+                        None
+                    } else {
+                        Some(ColoredStyle::from_sgr(value).map_err(|e| error(e.to_string()))?)
+                    };
+                    match key {
+                        "mt" => {
+                            self.selected_match = style;
+                            self.context_match = style;
+                        }
+                        "ms" => self.selected_match = style,
+                        "mc" => self.context_match = style,
+                        "sl" => self.selected_line = style,
+                        "cx" => self.context_line = style,
+                        "fn" => self.file_name = style,
+                        "ln" => self.line_number = style,
+                        "bn" => self.byte_offset = style,
+                        "se" => self.separator = style,
+                        _ => (),
+                    }
+                }
+                (_, None) => return Err(error(String::from("missing `=`"))),
+            }
+        }
+        Ok(())
+    }
+
+    /// The matching text in a selected line (`ms`).
+    pub fn selected_match(&self) -> Option<ColoredStyle> {
+        self.selected_match
+    }
+
+    /// The matching text in a context line (`mc`).
+    pub fn context_match(&self) -> Option<ColoredStyle> {
+        self.context_match
+    }
+
+    /// The non matching text of a selected line (`sl`), swapped with `cx` when `rv` is set.
+    pub fn selected_line(&self) -> Option<ColoredStyle> {
+        self.selected_line
+    }
+
+    /// The non matching text of a context line (`cx`), swapped with `sl` when `rv` is set.
+    pub fn context_line(&self) -> Option<ColoredStyle> {
+        self.context_line
+    }
+
+    /// The file names (`fn`).
+    pub fn file_name(&self) -> Option<ColoredStyle> {
+        self.file_name
+    }
+
+    /// The line numbers (`ln`).
+    pub fn line_number(&self) -> Option<ColoredStyle> {
+        self.line_number
+    }
+
+    /// The byte offsets (`bn`).
+    pub fn byte_offset(&self) -> Option<ColoredStyle> {
+        self.byte_offset
+    }
+
+    /// The separators between fields and groups of lines (`se`).
+    pub fn separator(&self) -> Option<ColoredStyle> {
+        self.separator
+    }
+
+    /// Whether `sl` and `cx` are swapped when `-v` is used (`rv`).
+    pub fn reverse(&self) -> bool {
+        self.reverse
+    }
+
+    /// Whether grep skips the "erase in line" sequences (`ne`).
+    pub fn no_erase(&self) -> bool {
+        self.no_erase
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use color::Color;
+
+    #[test]
+    fn defaults() {
+        let colors = GrepColors::default();
+
+        assert_eq!(
+            Some(ColoredStyle::new().bold().color(Color::Red)),
+            colors.selected_match()
+        );
+        assert_eq!(None, colors.selected_line());
+        assert_eq!(
+            Some(ColoredStyle::new().color(Color::Magenta)),
+            colors.file_name()
+        );
+        assert!(!colors.reverse());
+    }
+
+    #[test]
+    fn parse() {
+        let colors = GrepColors::parse("mt=01;32:fn=:sl=1:rv:ne").unwrap();
+
+        assert_eq!(
+            Some(ColoredStyle::new().bold().color(Color::Green)),
+            colors.selected_match()
+        );
+        assert_eq!(colors.selected_match(), colors.context_match());
+        assert_eq!(None, colors.file_name());
+        assert_eq!(Some(ColoredStyle::new().bold()), colors.selected_line());
+        assert!(colors.reverse());
+        assert!(colors.no_erase());
+    }
+
+    #[test]
+    fn unknown_keys_skipped() {
+        let colors = GrepColors::parse("ms=01;31:xx=1:yy:zz=not sgr:fn=34").unwrap();
+        assert_eq!(
+            Some(ColoredStyle::new().bold().color(Color::Red)),
+            colors.selected_match()
+        );
+        assert_eq!(
+            Some(ColoredStyle::new().color(Color::Blue)),
+            colors.file_name()
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            "invalid GREP_COLORS entry \"ms\": missing `=`",
+            GrepColors::parse("ms").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "invalid GREP_COLORS entry \"rv=1\": \"rv\" is a boolean and takes no value",
+            GrepColors::parse("rv=1").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "invalid GREP_COLORS entry \"fn=3x\": invalid code \"3x\" in SGR parameters \"3x\"",
+            GrepColors::parse("fn=3x").unwrap_err().to_string()
+        );
+    }
+}
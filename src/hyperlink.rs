@@ -0,0 +1,85 @@
+//! Hyperlinks, as OSC 8 sequences: `"\x1B]8;;URL\x1B\\text\x1B]8;;\x1B\\"`.
+
+/// Ends the current link, whatever it is.
+pub(crate) const CLOSE: &str = "\x1B]8;;\x1B\\";
+
+/// The target of a link. Links sharing an `id` are highlighted together by the terminal, even
+/// when split on several lines.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Hyperlink {
+    url: String,
+    id: Option<String>,
+}
+
+impl Hyperlink {
+    pub(crate) fn new(url: &str, id: Option<&str>) -> Hyperlink {
+        // only printable ASCII can go in the sequence: the rest is percent-encoded, so that an
+        // URL can't end the sequence early
+        let mut encoded = String::with_capacity(url.len());
+        for byte in url.bytes() {
+            if (0x20..0x7F).contains(&byte) {
+                encoded.push(char::from(byte));
+            } else {
+                encoded.push_str(&format!("%{:02X}", byte));
+            }
+        }
+
+        // `:` and `;` separate the parameters
+        let id = id.map(|id| {
+            id.chars()
+                .filter(|&c| (' '..'\x7F').contains(&c) && c != ':' && c != ';')
+                .collect()
+        });
+
+        Hyperlink { url: encoded, id }
+    }
+
+    /// The sequence starting the link.
+    pub(crate) fn open(&self) -> String {
+        match self.id {
+            Some(ref id) => format!("\x1B]8;id={};{}\x1B\\", id, self.url),
+            None => format!("\x1B]8;;{}\x1B\\", self.url),
+        }
+    }
+
+    /// Restarts this link after the end of the inner links of `input`.
+    pub(crate) fn escape_inner_links(&self, input: &str) -> String {
+        if !input.contains(CLOSE) {
+            return String::from(input);
+        }
+        input.replace(CLOSE, &format!("{}{}", CLOSE, self.open()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequences() {
+        let link = Hyperlink::new("https://example.com/a b", None);
+        assert_eq!("\x1B]8;;https://example.com/a b\x1B\\", link.open());
+
+        let link = Hyperlink::new("file:///tmp/é", Some("tmp"));
+        assert_eq!("\x1B]8;id=tmp;file:///tmp/%C3%A9\x1B\\", link.open());
+    }
+
+    #[test]
+    fn sanitization() {
+        let link = Hyperlink::new("http://a\x1B\\b\x07", Some("x;y:z\x1B"));
+        assert_eq!("\x1B]8;id=xyz;http://a%1B\\b%07\x1B\\", link.open());
+    }
+
+    #[test]
+    fn inner_links() {
+        let outer = Hyperlink::new("https://a", None);
+        let inner = Hyperlink::new("https://b", None);
+        let input = format!("x {}y{} z", inner.open(), CLOSE);
+
+        assert_eq!(
+            format!("x {}y{}{} z", inner.open(), CLOSE, outer.open()),
+            outer.escape_inner_links(&input)
+        );
+        assert_eq!("plain", outer.escape_inner_links("plain"));
+    }
+}
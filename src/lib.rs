@@ -26,17 +26,44 @@
 
 #[macro_use]
 extern crate lazy_static;
+#[cfg(unix)]
+extern crate libc;
+extern crate unicode_width;
 #[cfg(windows)]
 extern crate winconsole;
 
+#[cfg(feature = "serde")]
+extern crate serde;
+
 #[cfg(test)]
 extern crate rspec;
 
 mod color;
 pub mod control;
+pub mod cursor;
+pub mod cvd;
+pub mod diff;
+pub mod git_config;
+pub mod grep_colors;
+mod hyperlink;
+pub mod ls_colors;
+#[macro_use]
+pub mod markup;
+pub mod palette;
+#[cfg(feature = "serde")]
+mod serialization;
+mod sgr;
 mod style;
+pub mod table;
+pub mod terminal;
+pub mod testing;
+pub mod theme;
+pub mod width;
+pub mod wrap;
 
 pub use color::*;
+pub use sgr::SgrError;
+pub use style::{ColoredStyle, Styles};
 
 use std::convert::From;
 use std::fmt;
@@ -49,7 +76,13 @@ pub struct ColoredString {
     input: String,
     fgcolor: Option<AllColor>,
     bgcolor: Option<AllColor>,
+    underline_color: Option<AllColor>,
     style: style::Style,
+    // what has been explicitly turned off, so nested strings don't inherit it from their parent
+    style_off: style::Style,
+    fg_default: bool,
+    bg_default: bool,
+    hyperlink: Option<hyperlink::Hyperlink>,
 }
 
 /// The trait that enables something to be given color.
@@ -103,6 +136,13 @@ pub trait Colorize {
     fn on_true_color(self, r: u8, g: u8, b: u8) -> ColoredString;
     fn on_hex_color(self, code: u64) -> ColoredString;
     fn on_palette(self, code: u8) -> ColoredString;
+    /// Sets an RGB background color, and black or white text, the most readable on it.
+    fn on_true_color_auto(self, r: u8, g: u8, b: u8) -> ColoredString;
+    // Underline Colors
+    /// Sets the color of the underline (SGR 58), independently of the text color. It is only
+    /// visible together with one of the underline styles.
+    fn underline_color<S: Into<Color>>(self, color: S) -> ColoredString;
+    fn underline_true_color(self, r: u8, g: u8, b: u8) -> ColoredString;
     // Styles
     fn clear(self) -> ColoredString;
     fn normal(self) -> ColoredString;
@@ -118,11 +158,128 @@ pub trait Colorize {
     fn reversed(self) -> ColoredString;
     fn hidden(self) -> ColoredString;
     fn strikethrough(self) -> ColoredString;
+    /// Double underline (SGR 21). Some terminals render it as a plain underline.
+    fn double_underline(self) -> ColoredString;
+    /// Curly underline (SGR 4:3), as used by spell checkers in kitty, WezTerm or VTE.
+    fn curly_underline(self) -> ColoredString;
+    /// Dotted underline (SGR 4:4).
+    fn dotted_underline(self) -> ColoredString;
+    /// Dashed underline (SGR 4:5).
+    fn dashed_underline(self) -> ColoredString;
+    /// Line over the text (SGR 53).
+    fn overline(self) -> ColoredString;
+    /// Fast blinking (SGR 6). Most terminals blink at the same speed as `blink`.
+    fn rapid_blink(self) -> ColoredString;
+    /// Frame around the text (SGR 51), rarely supported.
+    fn framed(self) -> ColoredString;
+    /// Circle around the text (SGR 52), rarely supported.
+    fn encircled(self) -> ColoredString;
+    // Removing styles and colors
+    /// Removes the given style. When nested in a string having this style, it is turned off for
+    /// the inner string (`"\x1B[22m"` for bold, `"\x1B[23m"` for italic, ...).
+    fn remove_style(self, style: Styles) -> ColoredString;
+    fn not_bold(self) -> ColoredString;
+    fn not_italic(self) -> ColoredString;
+    fn not_underline(self) -> ColoredString;
+    /// Removes the foreground color. Nested, the string will inherit its parent color. Use
+    /// `Colorize::default_fg` to get the default terminal color instead.
+    fn no_fg(self) -> ColoredString;
+    /// Removes the background color. Nested, the string will inherit its parent background. Use
+    /// `Colorize::default_bg` to get the default terminal background instead.
+    fn no_bg(self) -> ColoredString;
+    /// Uses the default terminal foreground color (`"\x1B[39m"`), even when nested.
+    fn default_fg(self) -> ColoredString;
+    /// Uses the default terminal background color (`"\x1B[49m"`), even when nested.
+    fn default_bg(self) -> ColoredString;
+    // Semantic roles
+    /// Applies the style of `role` (like `"error"` or `"path"`) from the current theme. See the
+    /// `theme` module. Unknown roles, or a disabled colorization, leave the string as is.
+    fn role(self, role: &str) -> ColoredString;
+    // Hyperlinks
+    /// Makes the string a link to `url` (OSC 8), for the terminals supporting it. See
+    /// `control::SHOULD_HYPERLINK`: links are left out when unsupported, or when colorization is
+    /// disabled.
+    fn hyperlink(self, url: &str) -> ColoredString;
+    /// Like `Colorize::hyperlink`, the terminal highlighting together the links sharing an `id`.
+    fn hyperlink_with_id(self, url: &str, id: &str) -> ColoredString;
 }
 
 impl ColoredString {
     pub fn is_plain(&self) -> bool {
-        (self.bgcolor.is_none() && self.fgcolor.is_none() && self.style == style::CLEAR)
+        (self.bgcolor.is_none()
+            && self.fgcolor.is_none()
+            && self.underline_color.is_none()
+            && self.style == style::CLEAR
+            && self.style_off == style::CLEAR
+            && !self.fg_default
+            && !self.bg_default)
+    }
+
+    /// The number of columns the string takes in a terminal. Unlike `len()`, it ignores the
+    /// escape sequences of nested strings, and counts the characters by width: 2 for the wide
+    /// CJK characters and emojis, 0 for the combining marks and zero width joiners.
+    pub fn visible_width(&self) -> usize {
+        width::visible_width(&self.input)
+    }
+
+    /// Shortens the string to at most `width` columns, ending it with `ellipsis` when something
+    /// was cut. The colors and styles are kept, including the ones of nested strings.
+    pub fn truncate_to_width(&self, width: usize, ellipsis: &str) -> ColoredString {
+        ColoredString {
+            input: width::truncate(&self.input, width, ellipsis),
+            ..self.clone()
+        }
+    }
+
+    /// The contrast ratio between the foreground and background colors, when both are set. The
+    /// standard and palette colors are taken with their xterm values.
+    pub fn contrast_ratio(&self) -> Option<f64> {
+        let palette = palette::Palette::xterm();
+        match (self.fgcolor, self.bgcolor) {
+            (Some(fg), Some(bg)) => Some(palette.resolve(fg).contrast_ratio(palette.resolve(bg))),
+            _ => None,
+        }
+    }
+
+    /// The warning of `control::set_min_contrast`, when the contrast between the colors
+    /// displayed is too low.
+    fn low_contrast_warning(&self, min: f64, fg: AllColor, bg: AllColor) -> Option<String> {
+        let palette = palette::Palette::xterm();
+        let ratio = palette.resolve(fg).contrast_ratio(palette.resolve(bg));
+        if ratio >= min {
+            return None;
+        }
+        Some(format!(
+            "colored: low contrast ({:.2}:1) between {} and {} in {:?}",
+            ratio,
+            fg,
+            bg,
+            self.input
+        ))
+    }
+
+    /// Prints the warning of `control::set_min_contrast`, once by pair of colors.
+    fn check_contrast(&self) {
+        use control;
+        use std::sync::Mutex;
+
+        lazy_static! {
+            static ref WARNED: Mutex<Vec<(AllColor, AllColor)>> = Mutex::new(Vec::new());
+        }
+
+        // the colors of the colorblind-safe mode, when it is on
+        let (fg, bg) = match (cvd::displayed(self.fgcolor), cvd::displayed(self.bgcolor)) {
+            (Some(fg), Some(bg)) => (fg, bg),
+            _ => return,
+        };
+        let min = control::min_contrast();
+        if let Some(warning) = min.and_then(|min| self.low_contrast_warning(min, fg, bg)) {
+            let mut warned = WARNED.lock().unwrap_or_else(|err| err.into_inner());
+            if !warned.contains(&(fg, bg)) {
+                warned.push((fg, bg));
+                eprintln!("{}", warning);
+            }
+        }
     }
 
     #[cfg(not(feature = "no-color"))]
@@ -137,20 +294,48 @@ impl ColoredString {
         false
     }
 
+    /// The link to emit, if any.
+    #[cfg(not(feature = "no-color"))]
+    fn active_hyperlink(&self) -> Option<&hyperlink::Hyperlink> {
+        use control;
+
+        if self.has_colors() && control::SHOULD_HYPERLINK.should_hyperlink() {
+            self.hyperlink.as_ref()
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "no-color")]
+    fn active_hyperlink(&self) -> Option<&hyperlink::Hyperlink> {
+        None
+    }
+
     fn compute_style(&self) -> String {
         if !self.has_colors() || self.is_plain() {
             return String::new();
         }
 
         let mut res = String::from("\x1B[");
-        let mut has_wrote = if self.style != style::CLEAR {
-            res.push_str(&self.style.to_str());
+        // the off codes go first, so that "not bold but dimmed" still turns dimmed on
+        let style_off = style::Style::difference(self.style_off, self.style);
+        let mut has_wrote = if style_off != style::CLEAR {
+            res.push_str(&style_off.to_off_str());
             true
         } else {
             false
         };
 
-        if let Some(ref bgcolor) = self.bgcolor {
+        if self.style != style::CLEAR {
+            if has_wrote {
+                res.push(';');
+            }
+
+            res.push_str(&self.style.to_str());
+            has_wrote = true;
+        }
+
+        if let Some(ref bgcolor) = cvd::displayed(self.bgcolor) {
             if has_wrote {
                 res.push(';');
             }
@@ -161,10 +346,17 @@ impl ColoredString {
                 AllColor::Palette(code) => res.push_str(&format!("48;5;{}", code)),
             }
 
+            has_wrote = true;
+        } else if self.bg_default {
+            if has_wrote {
+                res.push(';');
+            }
+
+            res.push_str("49");
             has_wrote = true;
         }
 
-        if let Some(ref fgcolor) = self.fgcolor {
+        if let Some(ref fgcolor) = cvd::displayed(self.fgcolor) {
             if has_wrote {
                 res.push(';');
             }
@@ -174,6 +366,27 @@ impl ColoredString {
                 AllColor::True(code) => res.push_str(&code.to_fg_string()),
                 AllColor::Palette(code) => res.push_str(&format!("38;5;{}", code)),
             }
+
+            has_wrote = true;
+        } else if self.fg_default {
+            if has_wrote {
+                res.push(';');
+            }
+
+            res.push_str("39");
+            has_wrote = true;
+        }
+
+        if let Some(ref underline_color) = cvd::displayed(self.underline_color) {
+            if has_wrote {
+                res.push(';');
+            }
+
+            match underline_color {
+                AllColor::Color(c) => res.push_str(&format!("58;5;{}", c.to_palette_index())),
+                AllColor::True(code) => res.push_str(&code.to_underline_string()),
+                AllColor::Palette(code) => res.push_str(&format!("58;5;{}", code)),
+            }
         }
 
         res.push('m');
@@ -193,21 +406,15 @@ impl ColoredString {
             .map(|(idx, _)| idx)
             .collect();
 
-        let mut idx_in_matches = 0;
         let mut input = self.input.clone();
         input.reserve(matches.len() * style.len());
 
-        for offset in matches {
+        for (idx_in_matches, offset) in matches.into_iter().enumerate() {
             // shift the offset to the end of the reset sequence and take in account
             // the number of matches we have escaped (which shift the index to insert)
-            let mut offset = offset + reset.len() + idx_in_matches * style.len();
+            let offset = offset + reset.len() + idx_in_matches * style.len();
 
-            for cchar in style.chars() {
-                input.insert(offset, cchar);
-                offset += 1;
-            }
-
-            idx_in_matches += 1;
+            input.insert_str(offset, &style);
         }
 
         input
@@ -220,7 +427,12 @@ impl Default for ColoredString {
             input: String::default(),
             fgcolor: None,
             bgcolor: None,
+            underline_color: None,
             style: style::CLEAR,
+            style_off: style::CLEAR,
+            fg_default: false,
+            bg_default: false,
+            hyperlink: None,
         }
     }
 }
@@ -372,9 +584,33 @@ impl Colorize for ColoredString {
         }
     }
 
+    fn on_true_color_auto(self, r: u8, g: u8, b: u8) -> ColoredString {
+        let bg = TrueColor::new(r, g, b);
+        ColoredString {
+            fgcolor: Some(AllColor::True(bg.readable_foreground())),
+            bgcolor: Some(AllColor::True(bg)),
+            ..self
+        }
+    }
+
+    fn underline_color<S: Into<Color>>(self, color: S) -> ColoredString {
+        ColoredString {
+            underline_color: Some(AllColor::Color(color.into())),
+            ..self
+        }
+    }
+
+    fn underline_true_color(self, r: u8, g: u8, b: u8) -> ColoredString {
+        ColoredString {
+            underline_color: Some(AllColor::True(TrueColor::new(r, g, b))),
+            ..self
+        }
+    }
+
     fn clear(self) -> ColoredString {
         ColoredString {
             input: self.input,
+            hyperlink: self.hyperlink,
             ..ColoredString::default()
         }
     }
@@ -390,6 +626,82 @@ impl Colorize for ColoredString {
     def_style!(reversed, style::Styles::Reversed);
     def_style!(hidden, style::Styles::Hidden);
     def_style!(strikethrough, style::Styles::Strikethrough);
+    def_style!(double_underline, style::Styles::DoubleUnderline);
+    def_style!(curly_underline, style::Styles::CurlyUnderline);
+    def_style!(dotted_underline, style::Styles::DottedUnderline);
+    def_style!(dashed_underline, style::Styles::DashedUnderline);
+    def_style!(overline, style::Styles::Overline);
+    def_style!(rapid_blink, style::Styles::RapidBlink);
+    def_style!(framed, style::Styles::Framed);
+    def_style!(encircled, style::Styles::Encircled);
+
+    fn remove_style(self, style: Styles) -> ColoredString {
+        ColoredString {
+            style: style::Style::without(self.style, style),
+            style_off: style::Style::from_both(self.style_off, style),
+            ..self
+        }
+    }
+    fn not_bold(self) -> ColoredString {
+        self.remove_style(Styles::Bold)
+    }
+    fn not_italic(self) -> ColoredString {
+        self.remove_style(Styles::Italic)
+    }
+    fn not_underline(self) -> ColoredString {
+        self.remove_style(Styles::Underline)
+    }
+    fn no_fg(self) -> ColoredString {
+        ColoredString {
+            fgcolor: None,
+            fg_default: false,
+            ..self
+        }
+    }
+    fn no_bg(self) -> ColoredString {
+        ColoredString {
+            bgcolor: None,
+            bg_default: false,
+            ..self
+        }
+    }
+    fn default_fg(self) -> ColoredString {
+        ColoredString {
+            fgcolor: None,
+            fg_default: true,
+            ..self
+        }
+    }
+    fn default_bg(self) -> ColoredString {
+        ColoredString {
+            bgcolor: None,
+            bg_default: true,
+            ..self
+        }
+    }
+
+    fn role(self, role: &str) -> ColoredString {
+        if !self.has_colors() {
+            return self;
+        }
+
+        match theme::style_for(role) {
+            Some(style) => style.paint(self),
+            None => self,
+        }
+    }
+    fn hyperlink(self, url: &str) -> ColoredString {
+        ColoredString {
+            hyperlink: Some(hyperlink::Hyperlink::new(url, None)),
+            ..self
+        }
+    }
+    fn hyperlink_with_id(self, url: &str, id: &str) -> ColoredString {
+        ColoredString {
+            hyperlink: Some(hyperlink::Hyperlink::new(url, Some(id))),
+            ..self
+        }
+    }
 }
 
 macro_rules! def_str_color {
@@ -416,7 +728,7 @@ macro_rules! def_str_style {
     }
 }
 
-impl<'a> Colorize for &'a str {
+impl Colorize for &str {
     def_str_color!(fgcolor: black => Color::Black);
     fn red(self) -> ColoredString {
         ColoredString {
@@ -537,6 +849,23 @@ impl<'a> Colorize for &'a str {
             ..ColoredString::default()
         }
     }
+    fn on_true_color_auto(self, r: u8, g: u8, b: u8) -> ColoredString {
+        ColoredString::from(self).on_true_color_auto(r, g, b)
+    }
+    fn underline_color<S: Into<Color>>(self, color: S) -> ColoredString {
+        ColoredString {
+            underline_color: Some(AllColor::Color(color.into())),
+            input: String::from(self),
+            ..ColoredString::default()
+        }
+    }
+    fn underline_true_color(self, r: u8, g: u8, b: u8) -> ColoredString {
+        ColoredString {
+            underline_color: Some(AllColor::True(TrueColor::new(r, g, b))),
+            input: String::from(self),
+            ..ColoredString::default()
+        }
+    }
     fn clear(self) -> ColoredString {
         ColoredString {
             input: String::from(self),
@@ -556,20 +885,74 @@ impl<'a> Colorize for &'a str {
     def_str_style!(reversed, style::Styles::Reversed);
     def_str_style!(hidden, style::Styles::Hidden);
     def_str_style!(strikethrough, style::Styles::Strikethrough);
+    def_str_style!(double_underline, style::Styles::DoubleUnderline);
+    def_str_style!(curly_underline, style::Styles::CurlyUnderline);
+    def_str_style!(dotted_underline, style::Styles::DottedUnderline);
+    def_str_style!(dashed_underline, style::Styles::DashedUnderline);
+    def_str_style!(overline, style::Styles::Overline);
+    def_str_style!(rapid_blink, style::Styles::RapidBlink);
+    def_str_style!(framed, style::Styles::Framed);
+    def_str_style!(encircled, style::Styles::Encircled);
+
+    fn remove_style(self, style: Styles) -> ColoredString {
+        ColoredString::from(self).remove_style(style)
+    }
+    fn not_bold(self) -> ColoredString {
+        self.remove_style(Styles::Bold)
+    }
+    fn not_italic(self) -> ColoredString {
+        self.remove_style(Styles::Italic)
+    }
+    fn not_underline(self) -> ColoredString {
+        self.remove_style(Styles::Underline)
+    }
+    fn no_fg(self) -> ColoredString {
+        ColoredString::from(self)
+    }
+    fn no_bg(self) -> ColoredString {
+        ColoredString::from(self)
+    }
+    fn default_fg(self) -> ColoredString {
+        ColoredString::from(self).default_fg()
+    }
+    fn default_bg(self) -> ColoredString {
+        ColoredString::from(self).default_bg()
+    }
+    fn role(self, role: &str) -> ColoredString {
+        ColoredString::from(self).role(role)
+    }
+    fn hyperlink(self, url: &str) -> ColoredString {
+        ColoredString::from(self).hyperlink(url)
+    }
+    fn hyperlink_with_id(self, url: &str, id: &str) -> ColoredString {
+        ColoredString::from(self).hyperlink_with_id(url, id)
+    }
 }
 
 impl fmt::Display for ColoredString {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if !self.has_colors() || self.is_plain() {
+        let link = self.active_hyperlink();
+        if !self.has_colors() || (self.is_plain() && link.is_none()) {
             return (<String as fmt::Display>::fmt(&self.input, f));
         }
 
+        self.check_contrast();
+
         // XXX: see tests. Useful when nesting colored strings
-        let escaped_input = self.escape_inner_reset_sequences();
+        let mut escaped_input = self.escape_inner_reset_sequences();
+        if let Some(link) = link {
+            escaped_input = link.escape_inner_links(&escaped_input);
+            f.write_str(&link.open())?;
+        }
 
-        try!(f.write_str(&self.compute_style()));
-        try!(<String as fmt::Display>::fmt(&escaped_input, f));
-        try!(f.write_str("\x1B[0m"));
+        f.write_str(&self.compute_style())?;
+        <String as fmt::Display>::fmt(&escaped_input, f)?;
+        if !self.is_plain() {
+            f.write_str("\x1B[0m")?;
+        }
+        if link.is_some() {
+            f.write_str(hyperlink::CLOSE)?;
+        }
         Ok(())
     }
 }
@@ -628,6 +1011,78 @@ mod tests {
         // assert!(false)
     }
 
+    #[test]
+    fn hyperlinks() {
+        control::set_hyperlink_override(true);
+
+        assert_eq!(
+            "\x1B]8;;https://example.com\x1B\\docs\x1B]8;;\x1B\\",
+            "docs".hyperlink("https://example.com").to_string()
+        );
+        assert_eq!(
+            "\x1B]8;id=1;https://example.com\x1B\\\x1B[1;34mdocs\x1B[0m\x1B]8;;\x1B\\",
+            "docs"
+                .blue()
+                .hyperlink_with_id("https://example.com", "1")
+                .bold()
+                .to_string()
+        );
+        assert_eq!(
+            "docs".hyperlink("https://example.com"),
+            "docs".red().hyperlink("https://example.com").clear()
+        );
+    }
+
+    #[test]
+    fn nested_hyperlinks() {
+        control::set_hyperlink_override(true);
+
+        let inner = "a".red().hyperlink("https://a");
+        let outer = format!("x {} y", inner).hyperlink("https://b");
+        assert_eq!(
+            "\x1B]8;;https://b\x1B\\x \x1B]8;;https://a\x1B\\\x1B[31ma\x1B[0m\x1B]8;;\x1B\\\x1B]8;;https://b\x1B\\ y\x1B]8;;\x1B\\",
+            outer.to_string()
+        );
+    }
+
+    #[test]
+    fn readable_background() {
+        assert_eq!(
+            "a".true_color(0, 0, 0).on_true_color(255, 255, 0),
+            "a".on_true_color_auto(255, 255, 0)
+        );
+        assert_eq!(
+            "a".bold().true_color(255, 255, 255).on_true_color(0, 0, 128),
+            "a".bold().on_true_color_auto(0, 0, 128)
+        );
+        for &(r, g, b) in &[(0, 0, 0), (118, 118, 118), (119, 119, 119), (255, 0, 0)] {
+            assert!("a".on_true_color_auto(r, g, b).contrast_ratio().unwrap() >= 4.5);
+        }
+    }
+
+    #[test]
+    fn contrast_warnings() {
+        assert_eq!(None, "a".yellow().contrast_ratio());
+        let yellow = AllColor::Color(Color::Yellow);
+        let white = AllColor::Color(Color::White);
+        let a = "a".yellow().on_white();
+        assert_eq!(None, a.low_contrast_warning(1.0, yellow, white));
+        assert_eq!(
+            Some(String::from(
+                "colored: low contrast (1.35:1) between yellow and white in \"a\""
+            )),
+            a.low_contrast_warning(4.5, yellow, white)
+        );
+        let black = AllColor::Color(Color::Black);
+        assert_eq!(None, "a".black().on_white().low_contrast_warning(4.5, black, white));
+
+        // the colors checked are the displayed ones, like the safe green of `cvd`
+        let green = "a".green().on_black();
+        assert_eq!(None, green.low_contrast_warning(7.0, AllColor::Color(Color::Green), black));
+        let safe_green = cvd::safe_color(AllColor::Color(Color::Green));
+        assert!(green.low_contrast_warning(7.0, safe_green, black).is_some());
+    }
+
     #[test]
     fn compute_style_empty_string() {
         assert_eq!("", "".clear().compute_style());
@@ -702,6 +1157,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn compute_style_curly_underline() {
+        let curly = "\x1B[4:3m";
+
+        assert_eq!(curly, "".curly_underline().compute_style());
+    }
+
+    #[test]
+    fn compute_style_bold_overline_on_blue() {
+        let bold_overline_on_blue = "\x1B[1;53;44m";
+
+        assert_eq!(
+            bold_overline_on_blue,
+            "".overline().bold().on_blue().compute_style()
+        );
+    }
+
+    #[test]
+    fn compute_style_underline_color() {
+        let red_underline = "\x1B[58;5;1m";
+
+        assert_eq!(red_underline, "".underline_color("red").compute_style());
+    }
+
+    #[test]
+    fn compute_style_curly_underline_true_color() {
+        let curly_red_underline = "\x1B[4:3;34;58;2;255;0;0m";
+
+        assert_eq!(
+            curly_red_underline,
+            "".curly_underline()
+                .underline_true_color(255, 0, 0)
+                .blue()
+                .compute_style()
+        );
+    }
+
+    #[test]
+    fn underline_color_is_not_plain() {
+        assert!(!"".underline_true_color(255, 0, 0).is_plain());
+        assert!("".underline_true_color(255, 0, 0).clear().is_plain());
+    }
+
     #[test]
     fn escape_reset_sequence_spec_should_do_nothing_on_empty_strings() {
         let style = ColoredString::default();
@@ -762,6 +1260,56 @@ mod tests {
         assert_eq!(expected, output);
     }
 
+    #[test]
+    fn escape_reset_sequence_spec_should_restore_underline_color() {
+        let input = format!("start {} end", "hello".red());
+        let style = input.underline().underline_color(Color::Blue);
+
+        let output = style.escape_inner_reset_sequences();
+        let expected = "start \x1B[31mhello\x1B[0m\x1B[4;58;5;4m end";
+        assert_eq!(expected, output);
+    }
+
+    #[test]
+    fn remove_style_on_a_themed_string() {
+        let themed = "".bold().italic().red().on_blue();
+
+        assert_eq!("\x1B[22;3;44;31m", themed.clone().not_bold().compute_style());
+        assert_eq!("\x1B[23;1;31m", themed.not_italic().no_bg().compute_style());
+    }
+
+    #[test]
+    fn remove_style_then_add_it_back() {
+        assert_eq!("\x1B[1m", "".not_bold().bold().compute_style());
+    }
+
+    #[test]
+    fn not_bold_keeps_dimmed() {
+        assert_eq!("\x1B[22;2m", "".bold().dimmed().not_bold().compute_style());
+    }
+
+    #[test]
+    fn no_fg_is_plain() {
+        assert!("".red().no_fg().is_plain());
+        assert!(!"".red().default_fg().is_plain());
+    }
+
+    #[test]
+    fn compute_style_default_colors() {
+        assert_eq!("\x1B[49;39m", "".red().on_red().default_fg().default_bg().compute_style());
+        assert_eq!("\x1B[31m", "".default_fg().red().compute_style());
+    }
+
+    #[test]
+    fn escape_reset_sequence_spec_should_turn_off_styles_in_nested_strings() {
+        let input = format!("start {} end", "hello".not_bold().default_fg());
+        let style = input.bold().red();
+
+        let output = style.to_string();
+        let expected = "\x1B[1;31mstart \x1B[22;39mhello\x1B[0m\x1B[1;31m end\x1B[0m";
+        assert_eq!(expected, output);
+    }
+
     #[test]
     fn color_fn() {
         assert_eq!("blue".blue(), "blue".color("blue"))
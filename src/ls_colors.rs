@@ -0,0 +1,534 @@
+//! Styling of file names from the `LS_COLORS` environment variable, like GNU `ls` does.
+//!
+//! ```no_run
+//! use colored::ls_colors::LsColors;
+//! use std::fs;
+//! use std::path::Path;
+//!
+//! let ls_colors = LsColors::from_env();
+//! let path = Path::new("src");
+//! println!("{}", ls_colors.paint(path, fs::symlink_metadata(path).ok().as_ref()));
+//! ```
+
+use std::collections::HashMap;
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use style::ColoredStyle;
+use ColoredString;
+
+/// The default colors of GNU `ls`, used for the types missing from `LS_COLORS`.
+const GNU_DEFAULTS: &str = "di=01;34:ln=01;36:pi=33:so=01;35:bd=01;33:cd=01;33:ex=01;32:do=01;35:\
+                            su=37;41:sg=30;43:st=37;44:ow=34;42:tw=30;42";
+
+/// The file type keys of `LS_COLORS`.
+const TYPE_KEYS: &[&str] = &[
+    "no", "fi", "rs", "di", "ln", "mh", "pi", "so", "do", "bd", "cd", "or", "mi", "su", "sg", "ca",
+    "tw", "ow", "st", "ex", "lc", "rc", "ec", "cl",
+];
+
+/// An `LS_COLORS` entry that couldn't be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LsColorsError {
+    entry: String,
+    reason: String,
+}
+
+impl fmt::Display for LsColorsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid LS_COLORS entry {:?}: {}",
+            self.entry, self.reason
+        )
+    }
+}
+
+impl Error for LsColorsError {}
+
+/// The styles of an `LS_COLORS` specification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LsColors {
+    types: HashMap<String, ColoredStyle>,
+    // `*` patterns, in their order of definition
+    patterns: Vec<(String, ColoredStyle)>,
+    link_as_target: bool,
+}
+
+/// The GNU `ls` defaults.
+impl Default for LsColors {
+    fn default() -> Self {
+        let mut ls_colors = LsColors::empty();
+        ls_colors
+            .extend(GNU_DEFAULTS)
+            .expect("the GNU defaults are valid");
+        ls_colors
+    }
+}
+
+impl LsColors {
+    /// No style at all: every file is printed as is.
+    pub fn empty() -> LsColors {
+        LsColors {
+            types: HashMap::new(),
+            patterns: Vec::new(),
+            link_as_target: false,
+        }
+    }
+
+    /// Reads the `LS_COLORS` environment variable, on top of the GNU defaults. An invalid
+    /// variable is ignored, as GNU `ls` does.
+    pub fn from_env() -> LsColors {
+        env::var("LS_COLORS")
+            .ok()
+            .and_then(|spec| LsColors::parse(&spec).ok())
+            .unwrap_or_default()
+    }
+
+    /// Parses an `LS_COLORS` specification (`"di=01;34:ln=01;36:*.rs=38;5;208"`), on top of the
+    /// GNU defaults.
+    pub fn parse(spec: &str) -> Result<LsColors, LsColorsError> {
+        let mut ls_colors = LsColors::default();
+        ls_colors.extend(spec)?;
+        Ok(ls_colors)
+    }
+
+    /// Adds the entries of an `LS_COLORS` specification to these ones. An empty value (`"ca="`)
+    /// removes the style of its key.
+    pub fn extend(&mut self, spec: &str) -> Result<(), LsColorsError> {
+        for entry in spec.split(':').filter(|entry| !entry.is_empty()) {
+            let error = |reason: String| LsColorsError {
+                entry: String::from(entry),
+                reason,
+            };
+            let (key, value) = match entry.find('=') {
+                Some(idx) => (&entry[..idx], &entry[idx + 1..]),
+                None => return Err(error(String::from("missing `=`"))),
+            };
+
+            if key.starts_with('*') {
+                self.patterns.retain(|(pattern, _)| pattern != key);
+                if !value.is_empty() {
+                    let style = ColoredStyle::from_sgr(value).map_err(|e| error(e.to_string()))?;
+                    self.patterns.push((String::from(key), style));
+                }
+            } else if !TYPE_KEYS.contains(&key) {
+                return Err(error(format!("unknown key {:?}", key)));
+            } else if key == "ln" && value == "target" {
+                self.types.remove(key);
+                self.link_as_target = true;
+            } else if value.is_empty() {
+                self.types.remove(key);
+                if key == "ln" {
+                    self.link_as_target = false;
+                }
+            } else {
+                let style = ColoredStyle::from_sgr(value).map_err(|e| error(e.to_string()))?;
+                self.types.insert(String::from(key), style);
+                if key == "ln" {
+                    self.link_as_target = false;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The style of a file type key (`"di"`, `"ln"`...) or a pattern (`"*.rs"`).
+    pub fn get(&self, key: &str) -> Option<ColoredStyle> {
+        if key.starts_with('*') {
+            self.patterns
+                .iter()
+                .rev()
+                .find(|(pattern, _)| pattern == key)
+                .map(|&(_, style)| style)
+        } else {
+            self.types.get(key).cloned()
+        }
+    }
+
+    /// The style of `path`, following GNU `ls` precedence. `metadata` is the result of
+    /// `fs::symlink_metadata`, `None` meaning that the file is missing.
+    ///
+    /// Special permissions (setuid, sticky...) go first, then the executable bit, then the
+    /// patterns, which only apply to regular files. Among the patterns, the last matching one
+    /// wins, an exact case match being preferred to a case insensitive one.
+    pub fn style_for(&self, path: &Path, metadata: Option<&fs::Metadata>) -> Option<ColoredStyle> {
+        let metadata = match metadata {
+            Some(metadata) => metadata,
+            None => return self.type_style("mi"),
+        };
+
+        if metadata.file_type().is_symlink() {
+            return match fs::metadata(path) {
+                Ok(ref target) if self.link_as_target => {
+                    // the patterns are matched against the name of the target
+                    let target_path = fs::canonicalize(path).unwrap_or_else(|_| path.to_owned());
+                    self.style_for(&target_path, Some(target))
+                }
+                Ok(_) => self.type_style("ln"),
+                Err(_) => self.type_style("or"),
+            };
+        }
+
+        let key = file_type_key(self, metadata);
+        if key == "fi" {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            if let Some(style) = self.pattern_style(&name) {
+                return Some(style);
+            }
+        }
+        self.type_style(key)
+    }
+
+    /// Paints the name of `path` with its style. See `LsColors::style_for`.
+    pub fn paint(&self, path: &Path, metadata: Option<&fs::Metadata>) -> ColoredString {
+        let name = path.to_string_lossy();
+        match self.style_for(path, metadata) {
+            Some(style) => style.apply(&name),
+            None => ColoredString::from(name.as_ref()),
+        }
+    }
+
+    /// The style of a file type, with the fallbacks of GNU `ls`: missing files are styled as
+    /// orphan links, orphan links as links (unless links take the style of their target), and
+    /// regular files and orphan links otherwise use `no`.
+    fn type_style(&self, key: &str) -> Option<ColoredStyle> {
+        if let Some(style) = self.colored(key) {
+            return Some(style);
+        }
+        match key {
+            "mi" => self.type_style("or"),
+            "or" if !self.link_as_target && self.colored("ln").is_some() => self.type_style("ln"),
+            "fi" | "or" => self.colored("no"),
+            _ => None,
+        }
+    }
+
+    /// The style of a file type, unless it is missing or resets to no style at all, like the
+    /// `mh=00` of `dircolors`: GNU `ls` then classifies the files as if the key was absent.
+    fn colored(&self, key: &str) -> Option<ColoredStyle> {
+        self.types
+            .get(key)
+            .cloned()
+            .filter(|&style| style != ColoredStyle::new())
+    }
+
+    fn pattern_style(&self, name: &str) -> Option<ColoredStyle> {
+        let lower = name.to_lowercase();
+        let patterns = || self.patterns.iter().rev();
+
+        patterns()
+            .find(|(pattern, _)| glob_match(pattern, name))
+            .or_else(|| patterns().find(|(pattern, _)| glob_match(&pattern.to_lowercase(), &lower)))
+            .map(|&(_, style)| style)
+    }
+}
+
+#[cfg(unix)]
+fn file_type_key(ls_colors: &LsColors, metadata: &fs::Metadata) -> &'static str {
+    use std::os::unix::fs::{FileTypeExt, MetadataExt};
+
+    let colored = |key: &str| ls_colors.colored(key).is_some();
+    let mode = metadata.mode();
+    let file_type = metadata.file_type();
+    let (setuid, setgid, sticky, other_writable, executable) =
+        (0o4000, 0o2000, 0o1000, 0o0002, 0o0111);
+
+    if file_type.is_file() {
+        if mode & setuid != 0 && colored("su") {
+            "su"
+        } else if mode & setgid != 0 && colored("sg") {
+            "sg"
+        } else if mode & executable != 0 && colored("ex") {
+            "ex"
+        } else if metadata.nlink() > 1 && colored("mh") {
+            "mh"
+        } else {
+            "fi"
+        }
+    } else if file_type.is_dir() {
+        if mode & sticky != 0 && mode & other_writable != 0 && colored("tw") {
+            "tw"
+        } else if mode & other_writable != 0 && colored("ow") {
+            "ow"
+        } else if mode & sticky != 0 && colored("st") {
+            "st"
+        } else {
+            "di"
+        }
+    } else if file_type.is_fifo() {
+        "pi"
+    } else if file_type.is_socket() {
+        "so"
+    } else if file_type.is_block_device() {
+        "bd"
+    } else if file_type.is_char_device() {
+        "cd"
+    } else {
+        "or"
+    }
+}
+
+#[cfg(not(unix))]
+fn file_type_key(_ls_colors: &LsColors, metadata: &fs::Metadata) -> &'static str {
+    if metadata.is_dir() {
+        "di"
+    } else {
+        "fi"
+    }
+}
+
+/// Matches `name` against a pattern where `*` is any sequence of characters and `?` any character.
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // where to resume after the last `*`
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            n = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use color::Color;
+    use style::Styles;
+
+    /// The output of `dircolors` (GNU coreutils 9.1), the `LS_COLORS` of Debian and Ubuntu.
+    const DIRCOLORS: &str = "rs=0:di=01;34:ln=01;36:mh=00:pi=40;33:so=01;35:do=01;35:bd=40;33;01:\
+                             cd=40;33;01:or=40;31;01:mi=00:su=37;41:sg=30;43:ca=00:tw=30;42:\
+                             ow=34;42:st=37;44:ex=01;32:*.tar=01;31:*.tgz=01;31:*.arc=01;31:\
+                             *.arj=01;31:*.taz=01;31:*.lha=01;31:*.lz4=01;31:*.lzh=01;31:\
+                             *.lzma=01;31:*.tlz=01;31:*.txz=01;31:*.tzo=01;31:*.t7z=01;31:\
+                             *.zip=01;31:*.z=01;31:*.dz=01;31:*.gz=01;31:*.lrz=01;31:*.lz=01;31:\
+                             *.lzo=01;31:*.xz=01;31:*.zst=01;31:*.tzst=01;31:*.bz2=01;31:\
+                             *.bz=01;31:*.tbz=01;31:*.tbz2=01;31:*.tz=01;31:*.deb=01;31:\
+                             *.rpm=01;31:*.jar=01;31:*.war=01;31:*.ear=01;31:*.sar=01;31:\
+                             *.rar=01;31:*.alz=01;31:*.ace=01;31:*.zoo=01;31:*.cpio=01;31:\
+                             *.7z=01;31:*.rz=01;31:*.cab=01;31:*.wim=01;31:*.swm=01;31:\
+                             *.dwm=01;31:*.esd=01;31:*.avif=01;35:*.jpg=01;35:*.jpeg=01;35:\
+                             *.mjpg=01;35:*.mjpeg=01;35:*.gif=01;35:*.bmp=01;35:*.pbm=01;35:\
+                             *.pgm=01;35:*.ppm=01;35:*.tga=01;35:*.xbm=01;35:*.xpm=01;35:\
+                             *.tif=01;35:*.tiff=01;35:*.png=01;35:*.svg=01;35:*.svgz=01;35:\
+                             *.mng=01;35:*.pcx=01;35:*.mov=01;35:*.mpg=01;35:*.mpeg=01;35:\
+                             *.m2v=01;35:*.mkv=01;35:*.webm=01;35:*.webp=01;35:*.ogm=01;35:\
+                             *.mp4=01;35:*.m4v=01;35:*.mp4v=01;35:*.vob=01;35:*.qt=01;35:\
+                             *.nuv=01;35:*.wmv=01;35:*.asf=01;35:*.rm=01;35:*.rmvb=01;35:\
+                             *.flc=01;35:*.avi=01;35:*.fli=01;35:*.flv=01;35:*.gl=01;35:\
+                             *.dl=01;35:*.xcf=01;35:*.xwd=01;35:*.yuv=01;35:*.cgm=01;35:\
+                             *.emf=01;35:*.ogv=01;35:*.ogx=01;35:*.aac=00;36:*.au=00;36:\
+                             *.flac=00;36:*.m4a=00;36:*.mid=00;36:*.midi=00;36:*.mka=00;36:\
+                             *.mp3=00;36:*.mpc=00;36:*.ogg=00;36:*.ra=00;36:*.wav=00;36:\
+                             *.oga=00;36:*.opus=00;36:*.spx=00;36:*.xspf=00;36:*~=00;90:*#=00;90:\
+                             *.bak=00;90:*.old=00;90:*.orig=00;90:*.part=00;90:*.rej=00;90:\
+                             *.swp=00;90:*.tmp=00;90:*.dpkg-dist=00;90:*.dpkg-old=00;90:\
+                             *.ucf-dist=00;90:*.ucf-new=00;90:*.ucf-old=00;90:*.rpmnew=00;90:\
+                             *.rpmorig=00;90:*.rpmsave=00;90:";
+
+    #[test]
+    fn glob() {
+        assert!(glob_match("*.rs", "lib.rs"));
+        assert!(glob_match("*.rs", ".rs"));
+        assert!(!glob_match("*.rs", "lib.rsx"));
+        assert!(glob_match("*README", "README"));
+        assert!(glob_match("*.tar.?z", "x.tar.gz"));
+        assert!(glob_match("*a*b", "xxaxxbxb"));
+        assert!(!glob_match("*a*b", "xxaxxbxc"));
+    }
+
+    #[test]
+    fn parse() {
+        let ls_colors = LsColors::parse("di=01;31:*.rs=38;5;208:ex=").unwrap();
+
+        assert_eq!(
+            Some(ColoredStyle::new().bold().color(Color::Red)),
+            ls_colors.get("di")
+        );
+        assert_eq!(
+            Some(ColoredStyle::new().palette(208)),
+            ls_colors.get("*.rs")
+        );
+        assert_eq!(None, ls_colors.get("ex"));
+        // from the defaults
+        assert_eq!(
+            Some(ColoredStyle::new().bold().color(Color::Cyan)),
+            ls_colors.get("ln")
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            "invalid LS_COLORS entry \"zz=01\": unknown key \"zz\"",
+            LsColors::parse("di=01:zz=01").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "invalid LS_COLORS entry \"di\": missing `=`",
+            LsColors::parse("di").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "invalid LS_COLORS entry \"*.rs=1;x\": invalid code \"x\" in SGR parameters \"1;x\"",
+            LsColors::parse("*.rs=1;x").unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn missing_file() {
+        let ls_colors = LsColors::parse("mi=05").unwrap();
+        let missing = ls_colors.style_for(Path::new("/nowhere"), None);
+
+        assert_eq!(Some(ColoredStyle::new().with(Styles::Blink)), missing);
+    }
+
+    #[test]
+    fn fallbacks() {
+        let bold_cyan = Some(ColoredStyle::new().bold().color(Color::Cyan));
+        let ls_colors = LsColors::parse("no=33").unwrap();
+        assert_eq!(bold_cyan, ls_colors.type_style("or"));
+        assert_eq!(bold_cyan, ls_colors.type_style("mi"));
+        assert_eq!(
+            Some(ColoredStyle::new().color(Color::Yellow)),
+            ls_colors.type_style("fi")
+        );
+
+        let ls_colors = LsColors::parse("or=31").unwrap();
+        assert_eq!(
+            Some(ColoredStyle::new().color(Color::Red)),
+            ls_colors.style_for(Path::new("/nowhere"), None)
+        );
+
+        // links colored as their target have no style of their own
+        let ls_colors = LsColors::parse("ln=target:no=33").unwrap();
+        assert_eq!(
+            Some(ColoredStyle::new().color(Color::Yellow)),
+            ls_colors.type_style("or")
+        );
+        assert_eq!(None, LsColors::parse("ln=").unwrap().type_style("mi"));
+
+        // `00` is no color: missing files use `or`
+        let ls_colors = LsColors::parse("or=31:mi=00").unwrap();
+        assert_eq!(
+            Some(ColoredStyle::new().color(Color::Red)),
+            ls_colors.style_for(Path::new("/nowhere"), None)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn dircolors_defaults() {
+        let dir = env::temp_dir().join(format!("colored-dircolors-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let archive = dir.join("foo.tar");
+        fs::write(&archive, "").unwrap();
+        fs::hard_link(&archive, dir.join("bar.tar")).unwrap();
+
+        // `mh=00`: the hard links are regular files, styled by their extension
+        let ls_colors = LsColors::parse(DIRCOLORS).unwrap();
+        assert_eq!(
+            Some(ColoredStyle::new().bold().color(Color::Red)),
+            ls_colors.style_for(&archive, fs::symlink_metadata(&archive).ok().as_ref())
+        );
+        // `mi=00`: the missing files are styled as orphan links
+        assert_eq!(
+            Some(
+                ColoredStyle::new()
+                    .on_color(Color::Black)
+                    .color(Color::Red)
+                    .bold()
+            ),
+            ls_colors.style_for(Path::new("/nowhere"), None)
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn precedence() {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let dir = env::temp_dir().join(format!("colored-ls-colors-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let file = |name: &str, mode: u32| {
+            let path = dir.join(name);
+            fs::write(&path, "").unwrap();
+            fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+            path
+        };
+        let source = file("main.rs", 0o644);
+        let script = file("run.rs", 0o755);
+        let upper = file("LIB.RS", 0o644);
+        symlink(&source, dir.join("link")).unwrap();
+        symlink(dir.join("nothing"), dir.join("orphan")).unwrap();
+
+        let ls_colors = LsColors::parse("ex=01;32:*.rs=33:*.RS=34:or=31").unwrap();
+        let style =
+            |path: &Path| ls_colors.style_for(path, fs::symlink_metadata(path).ok().as_ref());
+
+        assert_eq!(
+            Some(ColoredStyle::new().color(Color::Yellow)),
+            style(&source)
+        );
+        // the executable bit goes before the patterns
+        assert_eq!(
+            Some(ColoredStyle::new().bold().color(Color::Green)),
+            style(&script)
+        );
+        // exact case first
+        assert_eq!(Some(ColoredStyle::new().color(Color::Blue)), style(&upper));
+        assert_eq!(
+            Some(ColoredStyle::new().bold().color(Color::Blue)),
+            style(&dir)
+        );
+        assert_eq!(
+            Some(ColoredStyle::new().bold().color(Color::Cyan)),
+            style(&dir.join("link"))
+        );
+        assert_eq!(
+            Some(ColoredStyle::new().color(Color::Red)),
+            style(&dir.join("orphan"))
+        );
+
+        // without `or`, orphan links are styled as links
+        let orphan = dir.join("orphan");
+        assert_eq!(
+            Some(ColoredStyle::new().color(Color::Magenta)),
+            LsColors::parse("ln=35")
+                .unwrap()
+                .style_for(&orphan, fs::symlink_metadata(&orphan).ok().as_ref())
+        );
+
+        let as_target = LsColors::parse("ln=target:*.rs=33").unwrap();
+        let link = dir.join("link");
+        assert_eq!(
+            Some(ColoredStyle::new().color(Color::Yellow)),
+            as_target.style_for(&link, fs::symlink_metadata(&link).ok().as_ref())
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
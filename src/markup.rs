@@ -0,0 +1,971 @@
+//! Color tags: checked at compile time with the `cformat!` family of macros, or parsed at runtime
+//! with `Template`.
+//!
+//! ```
+//! #[macro_use]
+//! extern crate colored;
+//!
+//! # fn main() {
+//! let file = "Cargo.toml";
+//! let message = cformat!("<red>error</red>: <bold>{}</bold> not found", file);
+//! cprintln!("<bright_yellow on_blue>warning</>: <i>{}</i> is {:>3}% full", "disk", 97);
+//! # }
+//! ```
+//!
+//! A tag holds space separated colors and styles: color names (`red`, `bright_red`), hexadecimal
+//! colors (`#ff8700`), backgrounds (`on_blue`, `on_#000000`) and styles (`bold`, `italic`...,
+//! or `b`, `i`, `u`). `</>` closes the last opened tag, and `<<` is a literal `<`.
+//!
+//! The macros can also be imported by path, without `#[macro_use]`:
+//!
+//! ```edition2018
+//! use colored::cprintln;
+//!
+//! cprintln!("<green>ok</green>");
+//! ```
+//!
+//! The tags are checked at compile time: an unknown color or a missing closing tag fails the
+//! build. The arguments are never parsed as tags, whatever they contain. For that, they are
+//! passed after the template: variables named in the template (`{name}`) are refused.
+//!
+//! ```compile_fail
+//! #[macro_use]
+//! extern crate colored;
+//!
+//! # fn main() {
+//! cprintln!("<reed>error</reed>");
+//! # }
+//! ```
+//!
+//! ```compile_fail
+//! #[macro_use]
+//! extern crate colored;
+//!
+//! # fn main() {
+//! let name = "<b>x";
+//! cprintln!("<red>{name}</red>");
+//! # }
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use color::{AllColor, TrueColor};
+use style::{ColoredStyle, Styles};
+use theme;
+
+const COLOR_NAMES: [&str; 18] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "purple",
+    "cyan",
+    "white",
+    "bright_black",
+    "bright_red",
+    "bright_green",
+    "bright_yellow",
+    "bright_blue",
+    "bright_magenta",
+    "bright_purple",
+    "bright_cyan",
+    "bright_white",
+];
+
+const STYLE_NAMES: [(&str, Styles); 19] = [
+    ("bold", Styles::Bold),
+    ("b", Styles::Bold),
+    ("dimmed", Styles::Dimmed),
+    ("italic", Styles::Italic),
+    ("i", Styles::Italic),
+    ("underline", Styles::Underline),
+    ("u", Styles::Underline),
+    ("blink", Styles::Blink),
+    ("reversed", Styles::Reversed),
+    ("hidden", Styles::Hidden),
+    ("strikethrough", Styles::Strikethrough),
+    ("double_underline", Styles::DoubleUnderline),
+    ("curly_underline", Styles::CurlyUnderline),
+    ("dotted_underline", Styles::DottedUnderline),
+    ("dashed_underline", Styles::DashedUnderline),
+    ("overline", Styles::Overline),
+    ("rapid_blink", Styles::RapidBlink),
+    ("framed", Styles::Framed),
+    ("encircled", Styles::Encircled),
+];
+
+// around the formatted arguments, so that their content is never parsed as tags
+const ARG_START: char = '\u{F8F0}';
+const ARG_END: char = '\u{F8F1}';
+
+/// Formats text with color tags into a `String`. See the `markup` module.
+#[macro_export]
+macro_rules! cformat {
+    ($fmt:literal $(, $arg:expr)* $(,)?) => {{
+        const _: () = $crate::markup::validate($fmt);
+        $crate::markup::render(&format!($fmt $(, $crate::markup::Arg(&$arg))*))
+    }};
+}
+
+/// `print!` with color tags. See the `markup` module.
+#[macro_export]
+macro_rules! cprint {
+    ($($arg:tt)*) => {
+        print!("{}", $crate::cformat!($($arg)*))
+    };
+}
+
+/// `println!` with color tags. See the `markup` module.
+#[macro_export]
+macro_rules! cprintln {
+    () => {
+        println!()
+    };
+    ($($arg:tt)*) => {
+        println!("{}", $crate::cformat!($($arg)*))
+    };
+}
+
+/// `eprint!` with color tags. See the `markup` module.
+#[macro_export]
+macro_rules! ceprint {
+    ($($arg:tt)*) => {
+        eprint!("{}", $crate::cformat!($($arg)*))
+    };
+}
+
+/// `eprintln!` with color tags. See the `markup` module.
+#[macro_export]
+macro_rules! ceprintln {
+    () => {
+        eprintln!()
+    };
+    ($($arg:tt)*) => {
+        eprintln!("{}", $crate::cformat!($($arg)*))
+    };
+}
+
+/// Wraps an argument of `cformat!`, keeping its formatting options.
+#[doc(hidden)]
+pub struct Arg<T>(pub T);
+
+macro_rules! impl_arg_fmt {
+    ($($trait:ident),*) => {
+        $(
+            impl<T: fmt::$trait> fmt::$trait for Arg<T> {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    use std::fmt::Write;
+
+                    f.write_char(ARG_START)?;
+                    self.0.fmt(f)?;
+                    f.write_char(ARG_END)
+                }
+            }
+        )*
+    };
+}
+
+impl_arg_fmt!(Display, Debug, LowerHex, UpperHex, Octal, Binary, LowerExp, UpperExp);
+
+const fn same_bytes(bytes: &[u8], start: usize, end: usize, name: &[u8]) -> bool {
+    if end - start != name.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < name.len() {
+        if bytes[start + i] != name[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn is_hex_color(bytes: &[u8], start: usize, end: usize) -> bool {
+    if bytes[start] != b'#' || (end - start != 7 && end - start != 4) {
+        return false;
+    }
+    let mut i = start + 1;
+    while i < end {
+        if !bytes[i].is_ascii_hexdigit() {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn is_color(bytes: &[u8], start: usize, end: usize) -> bool {
+    if is_hex_color(bytes, start, end) {
+        return true;
+    }
+    let mut i = 0;
+    while i < COLOR_NAMES.len() {
+        if same_bytes(bytes, start, end, COLOR_NAMES[i].as_bytes()) {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+const fn is_word(bytes: &[u8], start: usize, end: usize) -> bool {
+    if start == end {
+        return false;
+    }
+    if end - start > 3 && same_bytes(bytes, start, start + 3, b"on_") {
+        return is_color(bytes, start + 3, end);
+    }
+    let mut i = 0;
+    while i < STYLE_NAMES.len() {
+        if same_bytes(bytes, start, end, STYLE_NAMES[i].0.as_bytes()) {
+            return true;
+        }
+        i += 1;
+    }
+    is_color(bytes, start, end)
+}
+
+const fn is_spec(bytes: &[u8], start: usize, end: usize) -> bool {
+    let mut word_start = start;
+    let mut words = 0;
+    let mut i = start;
+    while i <= end {
+        if i == end || bytes[i] == b' ' {
+            if i > word_start {
+                if !is_word(bytes, word_start, i) {
+                    return false;
+                }
+                words += 1;
+            }
+            word_start = i + 1;
+        }
+        i += 1;
+    }
+    words > 0
+}
+
+const fn same_spec(bytes: &[u8], one: (usize, usize), two: (usize, usize)) -> bool {
+    let (start, end) = one;
+    if end - start != two.1 - two.0 {
+        return false;
+    }
+    let mut i = 0;
+    while i < end - start {
+        if bytes[start + i] != bytes[two.0 + i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Checks the tags of a `cformat!` template. Being `const`, it fails the build of the macros
+/// when they are invalid.
+#[doc(hidden)]
+pub const fn validate(markup: &str) {
+    let bytes = markup.as_bytes();
+    let mut open = [(0, 0); 32];
+    let mut depth = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            // placeholders, which may contain a `<` for alignment
+            b'{' if i + 1 < bytes.len() && bytes[i + 1] == b'{' => i += 2,
+            b'{' => {
+                // `format!` would capture the variable, and its content would be read as tags
+                if i + 1 < bytes.len()
+                    && (bytes[i + 1].is_ascii_alphabetic()
+                        || bytes[i + 1] == b'_'
+                        || bytes[i + 1] >= 0x80)
+                {
+                    panic!(
+                        "cformat!: pass the arguments after the template, instead of naming \
+                         variables"
+                    );
+                }
+                while i < bytes.len() && bytes[i] != b'}' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            b'<' if i + 1 < bytes.len() && bytes[i + 1] == b'<' => i += 2,
+            b'<' => {
+                let start = i + 1;
+                let mut end = start;
+                while end < bytes.len() && bytes[end] != b'>' {
+                    if bytes[end] == b'<' {
+                        panic!("cformat!: unclosed tag, use `<<` for a literal `<`");
+                    }
+                    end += 1;
+                }
+                if end == bytes.len() {
+                    panic!("cformat!: unclosed tag, use `<<` for a literal `<`");
+                }
+
+                if bytes[start] == b'/' {
+                    if depth == 0 {
+                        panic!("cformat!: closing tag without an opening one");
+                    }
+                    depth -= 1;
+                    if end != start + 1 && !same_spec(bytes, (start + 1, end), open[depth]) {
+                        panic!("cformat!: closing tag doesn't match the last opened one");
+                    }
+                } else {
+                    if !is_spec(bytes, start, end) {
+                        panic!("cformat!: unknown color or style in a tag");
+                    }
+                    if depth == open.len() {
+                        panic!("cformat!: tags are nested too deeply");
+                    }
+                    open[depth] = (start, end);
+                    depth += 1;
+                }
+                i = end + 1;
+            }
+            _ => i += 1,
+        }
+    }
+
+    if depth != 0 {
+        panic!("cformat!: a tag is never closed");
+    }
+}
+
+/// The style of a tag, like `"bold red on_black"`.
+pub(crate) fn parse_spec(spec: &str) -> Option<ColoredStyle> {
+    let parse_color = |word: &str| -> Option<AllColor> {
+        if word.starts_with('#') {
+            word.parse::<TrueColor>().ok().map(AllColor::True)
+        } else if COLOR_NAMES.contains(&word) {
+            word.replace('_', " ")
+                .replace("purple", "magenta")
+                .parse()
+                .ok()
+                .map(AllColor::Color)
+        } else {
+            None
+        }
+    };
+    let mut style = ColoredStyle::new();
+    let mut words = spec.split(' ').filter(|word| !word.is_empty()).peekable();
+
+    words.peek()?;
+    for word in words {
+        style = if let Some(bg) = word.strip_prefix("on_") {
+            style.bg(parse_color(bg)?)
+        } else if let Some(&(_, s)) = STYLE_NAMES.iter().find(|&&(name, _)| name == word) {
+            style.with(s)
+        } else {
+            style.fg(parse_color(word)?)
+        };
+    }
+    Some(style)
+}
+
+/// Replaces the tags of a formatted `cformat!` template by the escape sequences.
+#[doc(hidden)]
+pub fn render(formatted: &str) -> String {
+    let mut res = String::with_capacity(formatted.len());
+    let mut text = String::new();
+    // the opened tags, and the resulting style
+    let mut stack: Vec<(&str, ColoredStyle)> = Vec::new();
+    let mut in_arg = false;
+    let mut chars = formatted.char_indices().peekable();
+
+    let flush = |res: &mut String, text: &mut String, stack: &[(&str, ColoredStyle)]| {
+        if !text.is_empty() {
+            let style = stack.last().map(|&(_, s)| s).unwrap_or_default();
+            res.push_str(&style.apply(text).to_string());
+            text.clear();
+        }
+    };
+
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            ARG_START => in_arg = true,
+            ARG_END => in_arg = false,
+            '<' if !in_arg => {
+                if let Some(&(_, '<')) = chars.peek() {
+                    chars.next();
+                    text.push('<');
+                    continue;
+                }
+                let tag = formatted[idx + 1..]
+                    .find('>')
+                    .map(|end| &formatted[idx + 1..idx + 1 + end]);
+                let tag = match tag {
+                    Some(tag) => tag,
+                    None => {
+                        text.push(c);
+                        continue;
+                    }
+                };
+
+                if tag.starts_with('/') {
+                    flush(&mut res, &mut text, &stack);
+                    stack.pop();
+                } else if let Some(style) = parse_spec(tag) {
+                    flush(&mut res, &mut text, &stack);
+                    let parent = stack.last().map(|&(_, s)| s).unwrap_or_default();
+                    stack.push((tag, parent | style));
+                } else {
+                    // only possible with tags built by the arguments of `format!`
+                    text.push(c);
+                    continue;
+                }
+                for _ in 0..tag.chars().count() + 1 {
+                    chars.next();
+                }
+            }
+            _ => text.push(c),
+        }
+    }
+    flush(&mut res, &mut text, &stack);
+    res
+}
+
+/// A template parsing error, with the byte position where it happened.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MarkupError {
+    position: usize,
+    message: String,
+}
+
+impl MarkupError {
+    fn new<S: Into<String>>(position: usize, message: S) -> MarkupError {
+        MarkupError {
+            position,
+            message: message.into(),
+        }
+    }
+
+    /// The byte offset of the error in the template.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl fmt::Display for MarkupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl Error for MarkupError {}
+
+/// A style of a template tag. Roles are looked up in the theme when rendering.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TagStyle {
+    Fixed(ColoredStyle),
+    Role(String),
+}
+
+impl TagStyle {
+    fn resolve(&self) -> ColoredStyle {
+        match *self {
+            TagStyle::Fixed(style) => style,
+            TagStyle::Role(ref role) => theme::style_for(role).unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Field(String),
+}
+
+/// A template written at runtime, typically read from a configuration file, like
+/// `"{branch:bold green} [b]\[{status:red}\][/]"`.
+///
+/// - `[b red]text[/]` styles `text`. `[/]` closes the last opened tag, `[/b red]` checks that it
+///   closes the right one.
+/// - `{name}` and `{name:bold green}` are fields, whose values are given when rendering. They are
+///   never parsed as markup.
+/// - `\[`, `\]`, `\{`, `\}` and `\\` are literal characters. A `[` which can't start a tag, like
+///   the one of `[{status}]`, is literal as well.
+///
+/// The tags hold space separated words: the colors and styles of `cformat!`, palette indexes
+/// (`208`), `on` followed by a background color (`[white on red]`), `under` followed by an
+/// underline color (`[curly_underline under red]`), and theme roles marked by `@` (`[@error]`,
+/// see the `theme` module). Any other word is an error, so that typos are reported. The roles
+/// are looked up in the current theme when rendering, a role missing from it having no style:
+/// `roles` lists them, to check them against a theme.
+///
+/// ```
+/// use colored::markup::Template;
+///
+/// let template = Template::parse("{branch:bold green} [[@error]{status}[/]]").unwrap();
+/// let prompt = template.render(|field| match field {
+///     "branch" => Some(String::from("main")),
+///     "status" => Some(String::from("2 conflicts")),
+///     _ => None,
+/// });
+///
+/// let error = Template::parse("[bold red]oops[/bold]").unwrap_err();
+/// assert_eq!(14, error.position());
+/// let typo = Template::parse("{branch:bold gren}").unwrap_err();
+/// assert_eq!(13, typo.position());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Template {
+    parts: Vec<(Part, Vec<TagStyle>)>,
+}
+
+fn is_tag(content: &str) -> bool {
+    let body = content.strip_prefix('/').unwrap_or(content);
+    (content.starts_with('/') || !body.trim().is_empty())
+        && body
+            .chars()
+            .all(|c| c.is_alphanumeric() || " _-#@".contains(c))
+}
+
+/// Whether a word names a role, like `error` or `diff-added`.
+fn is_role(word: &str) -> bool {
+    word.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && word
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+/// The styles of a tag, `position` being the one of its first character.
+fn parse_tag(spec: &str, position: usize) -> Result<Vec<TagStyle>, MarkupError> {
+    let mut styles = Vec::new();
+    // "on" or "under", waiting for their color
+    let mut layer: Option<&str> = None;
+    let mut offset = 0;
+
+    for word in spec.split(' ') {
+        let word_position = position + offset;
+        offset += word.len() + 1;
+        if word.is_empty() {
+            continue;
+        }
+
+        let lower = word.to_lowercase();
+        if layer.is_none() && (lower == "on" || lower == "under") {
+            layer = Some(word);
+            continue;
+        }
+        let style = if let Some(layer) = layer.take() {
+            let color = parse_spec(&format!("on_{}", lower))
+                .and_then(|style| style.bgcolor())
+                .or_else(|| lower.parse().ok())
+                .ok_or_else(|| {
+                    MarkupError::new(word_position, format!("unknown color {:?}", word))
+                })?;
+            if layer.eq_ignore_ascii_case("on") {
+                ColoredStyle::new().bg(color)
+            } else {
+                ColoredStyle::new().underline_color(color)
+            }
+        } else if let Some(style) = parse_spec(&lower) {
+            style
+        } else if let Ok(color) = lower.parse::<u8>() {
+            ColoredStyle::new().palette(color)
+        } else if let Some(role) = word.strip_prefix('@') {
+            if !is_role(role) {
+                return Err(MarkupError::new(
+                    word_position,
+                    format!("invalid role name {:?}", word),
+                ));
+            }
+            styles.push(TagStyle::Role(String::from(role)));
+            continue;
+        } else {
+            return Err(MarkupError::new(
+                word_position,
+                format!("unknown color or style {:?}", word),
+            ));
+        };
+        styles.push(TagStyle::Fixed(style));
+    }
+
+    if let Some(layer) = layer {
+        return Err(MarkupError::new(
+            position + spec.len(),
+            format!("missing color after {:?}", layer),
+        ));
+    }
+    Ok(styles)
+}
+
+impl Template {
+    /// Parses a template, reporting the unknown tags, the unclosed ones...
+    pub fn parse(src: &str) -> Result<Template, MarkupError> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        // the opened tags: position, content and styles
+        let mut stack: Vec<(usize, &str, Vec<TagStyle>)> = Vec::new();
+        let mut chars = src.char_indices().peekable();
+
+        fn current(stack: &[(usize, &str, Vec<TagStyle>)]) -> Vec<TagStyle> {
+            stack.iter().flat_map(|tag| tag.2.iter().cloned()).collect()
+        }
+        let flush = |parts: &mut Vec<_>, text: &mut String, stack: &[_]| {
+            if !text.is_empty() {
+                parts.push((Part::Text(text.split_off(0)), current(stack)));
+            }
+        };
+
+        while let Some((pos, c)) = chars.next() {
+            // the end of a tag or a field, to skip
+            let end = match c {
+                '\\' => {
+                    match chars.peek() {
+                        Some(&(_, next)) if "[]{}\\".contains(next) => {
+                            text.push(next);
+                            chars.next();
+                        }
+                        _ => text.push(c),
+                    }
+                    continue;
+                }
+                '[' => {
+                    let content = src[pos + 1..]
+                        .find(']')
+                        .map(|len| &src[pos + 1..pos + 1 + len])
+                        .filter(|content| is_tag(content));
+                    let content = match content {
+                        Some(content) => content,
+                        None => {
+                            text.push(c);
+                            continue;
+                        }
+                    };
+
+                    flush(&mut parts, &mut text, &stack);
+                    if let Some(closing) = content.strip_prefix('/') {
+                        match stack.pop() {
+                            None => {
+                                return Err(MarkupError::new(
+                                    pos,
+                                    format!("[{}] closes no tag", content),
+                                ))
+                            }
+                            Some((_, opening, _))
+                                if !closing.trim().is_empty()
+                                    && closing.trim() != opening.trim() =>
+                            {
+                                return Err(MarkupError::new(
+                                    pos,
+                                    format!("[{}] doesn't close [{}]", content, opening),
+                                ))
+                            }
+                            Some(_) => (),
+                        }
+                    } else {
+                        stack.push((pos, content, parse_tag(content, pos + 1)?));
+                    }
+                    pos + content.len() + 1
+                }
+                '{' => {
+                    let len = src[pos + 1..]
+                        .find('}')
+                        .ok_or_else(|| MarkupError::new(pos, "unclosed field"))?;
+                    let field = &src[pos + 1..pos + 1 + len];
+                    let (name, styles) = match field.find(':') {
+                        Some(idx) => (&field[..idx], parse_tag(&field[idx + 1..], pos + idx + 2)?),
+                        None => (field, Vec::new()),
+                    };
+                    if name.trim().is_empty() {
+                        return Err(MarkupError::new(pos, "field without a name"));
+                    }
+
+                    flush(&mut parts, &mut text, &stack);
+                    let mut field_styles = current(&stack);
+                    field_styles.extend(styles);
+                    parts.push((Part::Field(String::from(name.trim())), field_styles));
+                    pos + len + 1
+                }
+                _ => {
+                    text.push(c);
+                    continue;
+                }
+            };
+            while chars.next_if(|&(idx, _)| idx <= end).is_some() {}
+        }
+
+        if let Some(&(pos, content, _)) = stack.last() {
+            return Err(MarkupError::new(pos, format!("unclosed tag [{}]", content)));
+        }
+        flush(&mut parts, &mut text, &stack);
+        Ok(Template { parts })
+    }
+
+    /// The names of the fields, in order of appearance.
+    pub fn fields(&self) -> impl Iterator<Item = &str> {
+        self.parts.iter().filter_map(|part| match part.0 {
+            Part::Field(ref name) => Some(name.as_str()),
+            Part::Text(_) => None,
+        })
+    }
+
+    /// The roles used by the tags, in order of appearance, without duplicates.
+    pub fn roles(&self) -> Vec<&str> {
+        let mut roles = Vec::new();
+        for style in self.parts.iter().flat_map(|part| part.1.iter()) {
+            if let TagStyle::Role(ref role) = *style {
+                if !roles.contains(&role.as_str()) {
+                    roles.push(role.as_str());
+                }
+            }
+        }
+        roles
+    }
+
+    /// Renders the template, `values` giving the value of each field. The fields without value
+    /// are left empty. The theme roles are those of the current theme.
+    pub fn render<F>(&self, mut values: F) -> String
+    where
+        F: FnMut(&str) -> Option<String>,
+    {
+        let mut res = String::new();
+
+        for (part, styles) in &self.parts {
+            let value;
+            let text = match *part {
+                Part::Text(ref text) => text,
+                Part::Field(ref name) => {
+                    value = values(name).unwrap_or_default();
+                    &value
+                }
+            };
+            if text.is_empty() {
+                continue;
+            }
+
+            let style = styles
+                .iter()
+                .fold(ColoredStyle::new(), |style, tag| style | tag.resolve());
+            res.push_str(&style.apply(text).to_string());
+        }
+        res
+    }
+}
+
+impl FromStr for Template {
+    type Err = MarkupError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Template::parse(src)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use color::Color;
+    use Colorize;
+
+    #[test]
+    fn plain_text() {
+        assert_eq!("hello", cformat!("hello"));
+        assert_eq!("a < b", cformat!("a << b"));
+        assert_eq!("1 < 2 {}", cformat!("{} << {} {{}}", 1, 2));
+    }
+
+    #[test]
+    fn tags() {
+        assert_eq!(
+            format!("{}: {} not found", "error".red(), "toto".bold()),
+            cformat!("<red>error</red>: <bold>{}</bold> not found", "toto")
+        );
+        assert_eq!(
+            "warning".bright_yellow().on_blue().italic().to_string(),
+            cformat!("<bright_yellow on_blue i>warning</>")
+        );
+        assert_eq!(
+            "x".true_color(255, 135, 0)
+                .on_true_color(0, 0, 0)
+                .to_string(),
+            cformat!("<#ff8700 on_#000>x</>")
+        );
+    }
+
+    #[test]
+    fn nested_tags() {
+        let expected = format!("{}{}{}", "a".red(), "b".red().bold(), "c".red());
+        assert_eq!(expected, cformat!("<red>a<bold>b</bold>c</red>"));
+        assert_eq!(
+            "b".blue().bold().to_string(),
+            cformat!("<red bold><blue>b</blue></red bold>")
+        );
+    }
+
+    #[test]
+    fn arguments_are_not_parsed() {
+        assert_eq!(
+            format!("{}", "<red>x</red>".bold()),
+            cformat!("<bold>{}</bold>", "<red>x</red>")
+        );
+        assert_eq!(
+            format!("{}", "  <b>".bold()),
+            cformat!("<bold>{:>5}</bold>", "<b>")
+        );
+        assert_eq!("ff 0x1 [1]", cformat!("{:x} {:#x} {:?}", 255, 1, [1]));
+
+        let name = "<b>x";
+        assert_eq!(
+            format!("{}", "<b>x".red()),
+            cformat!("<red>{}</red>", name)
+        );
+        // the width may still name a variable
+        let width = 4;
+        assert_eq!(
+            format!("{}", "<b>x".red()),
+            cformat!("<red>{:width$}</red>", name)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "pass the arguments after the template")]
+    fn captured_arguments_refused() {
+        validate("<red>{name}</red>");
+    }
+
+    #[test]
+    fn alignment_in_placeholders() {
+        assert_eq!("x  |", cformat!("{:<3}|", "x"));
+    }
+
+    fn render(template: &str) -> String {
+        Template::parse(template)
+            .unwrap()
+            .render(|field| Some(field.to_uppercase()))
+    }
+
+    #[test]
+    fn template_tags() {
+        assert_eq!("plain", render("plain"));
+        assert_eq!(
+            format!("{}{}", "a".red().bold(), "b".red()),
+            render("[red][b]a[/b]b[/red]")
+        );
+        assert_eq!(
+            "x".white().on_red().to_string(),
+            render("[white on red]x[/white on red]")
+        );
+        assert_eq!(
+            "x".palette(208).on_true_color(0, 0, 0).to_string(),
+            render("[208 on_#000]x[/]")
+        );
+    }
+
+    #[test]
+    fn template_fields() {
+        let template = Template::parse("{branch:bold green} [{status:red}]").unwrap();
+
+        assert_eq!(
+            vec!["branch", "status"],
+            template.fields().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            format!("{} [{}]", "BRANCH".bold().green(), "STATUS".red()),
+            template.render(|field| Some(field.to_uppercase()))
+        );
+        assert_eq!(" []", template.render(|_| None));
+        assert_eq!(
+            format!("{}", "[b]".italic().blue()),
+            Template::parse("[i]{x:blue}[/]")
+                .unwrap()
+                .render(|_| Some(String::from("[b]")))
+        );
+    }
+
+    #[test]
+    fn template_escapes() {
+        assert_eq!("[b]{x}\\ \\n", render("\\[b\\]\\{x\\}\\\\ \\n"));
+        assert_eq!("[] [ ] a]", render("[] [ ] a]"));
+    }
+
+    #[test]
+    fn template_roles() {
+        use theme::{with_theme, Theme};
+
+        // parsed without the role in the theme, resolved when rendering
+        let template = with_theme(Theme::new(), || {
+            Template::parse("[@error bold]x[/] {y:@hint}").unwrap()
+        });
+        let theme = Theme::new().with_role("error", ColoredStyle::new().color(Color::Red));
+
+        assert_eq!(
+            format!("{} y", "x".red().bold()),
+            with_theme(theme, || template.render(|_| Some(String::from("y"))))
+        );
+        assert_eq!(vec!["error", "hint"], template.roles());
+    }
+
+    #[test]
+    fn template_errors() {
+        let error = |template: &str| Template::parse(template).unwrap_err().to_string();
+
+        assert_eq!(
+            "unknown color or style \"#ree\" at position 5",
+            error("a [b #ree]x[/]")
+        );
+        assert_eq!("unclosed tag [red] at position 2", error("a [red]x"));
+        assert_eq!("[/] closes no tag at position 1", error("x[/]"));
+        assert_eq!(
+            "[/red] doesn't close [b] at position 8",
+            error("[red][b][/red][/]")
+        );
+        assert_eq!("unclosed field at position 2", error("a {x"));
+        assert_eq!("field without a name at position 0", error("{:red}"));
+        assert_eq!(
+            "unknown color or style \"300\" at position 3",
+            error("{x:300}")
+        );
+        // the typos are not taken for roles
+        assert_eq!(
+            "unknown color or style \"bright_gren\" at position 3",
+            error("{x:bright_gren}")
+        );
+        assert_eq!("unknown color or style \"bodl\" at position 1", error("[bodl]x[/]"));
+        assert_eq!("invalid role name \"@\" at position 3", error("[b @]x[/]"));
+        assert_eq!(
+            "missing color after \"on\" at position 9",
+            error("[white on]x[/]")
+        );
+        assert_eq!(
+            "missing color after \"under\" at position 16",
+            error("[underline under]x[/]")
+        );
+        assert_eq!(1, "[[bold]x".parse::<Template>().unwrap_err().position());
+    }
+
+    #[test]
+    fn validate_valid() {
+        validate("<red>a</red><b i on_#abcdef>b</><<{:<5}");
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown color or style")]
+    fn validate_unknown_tag() {
+        validate("<reed>a</reed>");
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't match")]
+    fn validate_mismatched_tags() {
+        validate("<red>a</blue>");
+    }
+
+    #[test]
+    #[should_panic(expected = "never closed")]
+    fn validate_unclosed_tag() {
+        validate("<red>a");
+    }
+
+    #[test]
+    #[should_panic(expected = "use `<<`")]
+    fn validate_lonely_bracket() {
+        validate("a < b");
+    }
+}
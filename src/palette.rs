@@ -0,0 +1,352 @@
+//! The RGB values terminals display the 16 standard colors and the 256 palette indexes with,
+//! and the palettes of popular terminals and color schemes.
+//!
+//! ```
+//! use colored::*;
+//! use colored::palette::Palette;
+//!
+//! let palette = Palette::xterm();
+//! assert_eq!(TrueColor::new(205, 0, 0), palette.color(Color::Red));
+//! assert_eq!(TrueColor::new(255, 135, 0), palette.index(208));
+//!
+//! let dracula = Palette::named("dracula").unwrap();
+//! assert_eq!(Color::BrightMagenta, dracula.nearest_color(TrueColor::new(255, 140, 220)));
+//! ```
+//!
+//! Color scheme files of terminals are read with `Palette::import`, and written with
+//! `Palette::export`.
+
+use color::{AllColor, Color, TrueColor};
+
+mod formats;
+
+pub use self::formats::{Format, ImportError};
+
+/// The colors of xterm, the default of most terminals, from black to bright white.
+pub(crate) const XTERM_COLORS: [TrueColor; 16] = [
+    TrueColor::new(0x00, 0x00, 0x00),
+    TrueColor::new(0xcd, 0x00, 0x00),
+    TrueColor::new(0x00, 0xcd, 0x00),
+    TrueColor::new(0xcd, 0xcd, 0x00),
+    TrueColor::new(0x00, 0x00, 0xee),
+    TrueColor::new(0xcd, 0x00, 0xcd),
+    TrueColor::new(0x00, 0xcd, 0xcd),
+    TrueColor::new(0xe5, 0xe5, 0xe5),
+    TrueColor::new(0x7f, 0x7f, 0x7f),
+    TrueColor::new(0xff, 0x00, 0x00),
+    TrueColor::new(0x00, 0xff, 0x00),
+    TrueColor::new(0xff, 0xff, 0x00),
+    TrueColor::new(0x5c, 0x5c, 0xff),
+    TrueColor::new(0xff, 0x00, 0xff),
+    TrueColor::new(0x00, 0xff, 0xff),
+    TrueColor::new(0xff, 0xff, 0xff),
+];
+
+/// The levels of the channels in the 6x6x6 color cube of the indexes 16 to 231.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The names of the built-in palettes, for `Palette::named`.
+pub const NAMES: [&str; 9] = [
+    "xterm",
+    "vga",
+    "windows-10",
+    "solarized-dark",
+    "solarized-light",
+    "dracula",
+    "gruvbox",
+    "nord",
+    "tango",
+];
+
+/// The 16 colors of `0xrrggbb` values.
+fn colors(hex: [u64; 16]) -> [TrueColor; 16] {
+    let mut colors = [TrueColor::new(0, 0, 0); 16];
+    for (color, &hex) in colors.iter_mut().zip(hex.iter()) {
+        *color = TrueColor::from_hex(hex);
+    }
+    colors
+}
+
+fn distance(a: TrueColor, b: TrueColor) -> i32 {
+    let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).pow(2);
+    d(a.r(), b.r()) + d(a.g(), b.g()) + d(a.b(), b.b())
+}
+
+/// The RGB value of each of the 256 palette indexes, the 16 first ones being the standard
+/// colors, and the default foreground and background colors when they are known.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Palette {
+    colors: [TrueColor; 256],
+    foreground: Option<TrueColor>,
+    background: Option<TrueColor>,
+}
+
+impl Palette {
+    /// The palette of xterm: its 16 colors, then the color cube and the grays shared by most
+    /// terminals.
+    pub fn xterm() -> Palette {
+        Palette::with_colors(XTERM_COLORS).with_defaults(0x000000, 0xffffff)
+    }
+
+    /// The colors of the VGA text mode, and of the Linux console.
+    pub fn vga() -> Palette {
+        Palette::with_colors(colors([
+            0x000000, 0xaa0000, 0x00aa00, 0xaa5500, 0x0000aa, 0xaa00aa, 0x00aaaa, 0xaaaaaa,
+            0x555555, 0xff5555, 0x55ff55, 0xffff55, 0x5555ff, 0xff55ff, 0x55ffff, 0xffffff,
+        ]))
+        .with_defaults(0xaaaaaa, 0x000000)
+    }
+
+    /// The colors of the Windows 10 console, the "Campbell" scheme of Windows Terminal.
+    pub fn windows_10() -> Palette {
+        Palette::with_colors(colors([
+            0x0c0c0c, 0xc50f1f, 0x13a10e, 0xc19c00, 0x0037da, 0x881798, 0x3a96dd, 0xcccccc,
+            0x767676, 0xe74856, 0x16c60c, 0xf9f1a5, 0x3b78ff, 0xb4009e, 0x61d6d6, 0xf2f2f2,
+        ]))
+        .with_defaults(0xcccccc, 0x0c0c0c)
+    }
+
+    /// Solarized, the bright colors being its grays and its orange and violet.
+    pub fn solarized_dark() -> Palette {
+        Palette::with_colors(colors([
+            0x073642, 0xdc322f, 0x859900, 0xb58900, 0x268bd2, 0xd33682, 0x2aa198, 0xeee8d5,
+            0x002b36, 0xcb4b16, 0x586e75, 0x657b83, 0x839496, 0x6c71c4, 0x93a1a1, 0xfdf6e3,
+        ]))
+        .with_defaults(0x839496, 0x002b36)
+    }
+
+    /// Solarized for light backgrounds, which only changes the default colors of the dark one.
+    pub fn solarized_light() -> Palette {
+        Palette::solarized_dark().with_defaults(0x657b83, 0xfdf6e3)
+    }
+
+    pub fn dracula() -> Palette {
+        Palette::with_colors(colors([
+            0x21222c, 0xff5555, 0x50fa7b, 0xf1fa8c, 0xbd93f9, 0xff79c6, 0x8be9fd, 0xf8f8f2,
+            0x6272a4, 0xff6e6e, 0x69ff94, 0xffffa5, 0xd6acff, 0xff92df, 0xa4ffff, 0xffffff,
+        ]))
+        .with_defaults(0xf8f8f2, 0x282a36)
+    }
+
+    /// Gruvbox, in its dark variant.
+    pub fn gruvbox() -> Palette {
+        Palette::with_colors(colors([
+            0x282828, 0xcc241d, 0x98971a, 0xd79921, 0x458588, 0xb16286, 0x689d6a, 0xa89984,
+            0x928374, 0xfb4934, 0xb8bb26, 0xfabd2f, 0x83a598, 0xd3869b, 0x8ec07c, 0xebdbb2,
+        ]))
+        .with_defaults(0xebdbb2, 0x282828)
+    }
+
+    pub fn nord() -> Palette {
+        Palette::with_colors(colors([
+            0x3b4252, 0xbf616a, 0xa3be8c, 0xebcb8b, 0x81a1c1, 0xb48ead, 0x88c0d0, 0xe5e9f0,
+            0x4c566a, 0xbf616a, 0xa3be8c, 0xebcb8b, 0x81a1c1, 0xb48ead, 0x8fbcbb, 0xeceff4,
+        ]))
+        .with_defaults(0xd8dee9, 0x2e3440)
+    }
+
+    /// The colors of the Tango project, the default of GNOME Terminal for a long time.
+    pub fn tango() -> Palette {
+        Palette::with_colors(colors([
+            0x2e3436, 0xcc0000, 0x4e9a06, 0xc4a000, 0x3465a4, 0x75507b, 0x06989a, 0xd3d7cf,
+            0x555753, 0xef2929, 0x8ae234, 0xfce94f, 0x729fcf, 0xad7fa8, 0x34e2e2, 0xeeeeec,
+        ]))
+        .with_defaults(0xd3d7cf, 0x2e3436)
+    }
+
+    fn with_defaults(mut self, foreground: u64, background: u64) -> Palette {
+        self.foreground = Some(TrueColor::from_hex(foreground));
+        self.background = Some(TrueColor::from_hex(background));
+        self
+    }
+
+    /// The built-in palette of this name, one of `NAMES`. The case, spaces and underscores
+    /// are ignored: `"Solarized Dark"` is `"solarized-dark"`.
+    pub fn named(name: &str) -> Option<Palette> {
+        let name = name.trim().to_lowercase().replace(&[' ', '_'][..], "-");
+        let palette = match name.as_str() {
+            "xterm" => Palette::xterm(),
+            "vga" => Palette::vga(),
+            "windows-10" => Palette::windows_10(),
+            "solarized-dark" => Palette::solarized_dark(),
+            "solarized-light" => Palette::solarized_light(),
+            "dracula" => Palette::dracula(),
+            "gruvbox" => Palette::gruvbox(),
+            "nord" => Palette::nord(),
+            "tango" => Palette::tango(),
+            _ => return None,
+        };
+        Some(palette)
+    }
+
+    /// A palette with the given 16 colors, from black to bright white, then the color cube and
+    /// the grays of xterm.
+    pub fn with_colors(colors: [TrueColor; 16]) -> Palette {
+        let mut all = [TrueColor::new(0, 0, 0); 256];
+        all[..16].copy_from_slice(&colors);
+        for idx in 0..216 {
+            all[16 + idx] = TrueColor::new(
+                CUBE_LEVELS[idx / 36],
+                CUBE_LEVELS[idx / 6 % 6],
+                CUBE_LEVELS[idx % 6],
+            );
+        }
+        for idx in 0..24 {
+            let level = 8 + 10 * idx as u8;
+            all[232 + idx] = TrueColor::new(level, level, level);
+        }
+        Palette {
+            colors: all,
+            foreground: None,
+            background: None,
+        }
+    }
+
+    /// The RGB value of one of the 16 standard colors.
+    pub fn color(&self, color: Color) -> TrueColor {
+        self.colors[usize::from(color.to_palette_index())]
+    }
+
+    /// The RGB value of a palette index.
+    pub fn index(&self, index: u8) -> TrueColor {
+        self.colors[usize::from(index)]
+    }
+
+    /// The RGB value of any color.
+    pub fn resolve(&self, color: AllColor) -> TrueColor {
+        match color {
+            AllColor::Color(color) => self.color(color),
+            AllColor::True(color) => color,
+            AllColor::Palette(index) => self.index(index),
+        }
+    }
+
+    /// The standard color closest to `color` in this palette, to display it where RGB colors
+    /// aren't supported.
+    pub fn nearest_color(&self, color: TrueColor) -> Color {
+        let index = (0..16)
+            .min_by_key(|&idx| distance(self.colors[idx], color))
+            .unwrap_or(0);
+        Color::from_palette_index(index as u8).unwrap_or(Color::Black)
+    }
+
+    /// The palette index closest to `color` in this palette.
+    pub fn nearest_index(&self, color: TrueColor) -> u8 {
+        (0..256)
+            .min_by_key(|&idx| distance(self.colors[idx], color))
+            .unwrap_or(0) as u8
+    }
+
+    /// The default color of the text, when it is known.
+    pub fn foreground(&self) -> Option<TrueColor> {
+        self.foreground
+    }
+
+    /// The default background color, when it is known.
+    pub fn background(&self) -> Option<TrueColor> {
+        self.background
+    }
+
+    pub fn set_foreground(&mut self, color: TrueColor) {
+        self.foreground = Some(color);
+    }
+
+    pub fn set_background(&mut self, color: TrueColor) {
+        self.background = Some(color);
+    }
+
+    pub fn set_color(&mut self, color: Color, value: TrueColor) {
+        self.set_index(color.to_palette_index(), value);
+    }
+
+    pub fn set_index(&mut self, index: u8, value: TrueColor) {
+        self.colors[usize::from(index)] = value;
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Palette {
+        Palette::xterm()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xterm() {
+        let palette = Palette::xterm();
+        assert_eq!(
+            TrueColor::new(0x5c, 0x5c, 0xff),
+            palette.color(Color::BrightBlue)
+        );
+        assert_eq!(TrueColor::new(0, 0, 0), palette.index(16));
+        assert_eq!(TrueColor::new(0, 0, 0x5f), palette.index(17));
+        assert_eq!(TrueColor::new(0xd7, 0x87, 0xaf), palette.index(175));
+        assert_eq!(TrueColor::new(255, 255, 255), palette.index(231));
+        assert_eq!(TrueColor::new(8, 8, 8), palette.index(232));
+        assert_eq!(TrueColor::new(0xee, 0xee, 0xee), palette.index(255));
+    }
+
+    #[test]
+    fn named() {
+        for name in &NAMES {
+            assert!(Palette::named(name).is_some(), "{}", name);
+        }
+        assert_eq!(Some(Palette::windows_10()), Palette::named("Windows 10"));
+        assert_eq!(
+            Some(Palette::solarized_dark()),
+            Palette::named("solarized_dark")
+        );
+        assert_eq!(None, Palette::named("unknown"));
+        assert_eq!(
+            Palette::solarized_dark().color(Color::Red),
+            Palette::solarized_light().color(Color::Red)
+        );
+        assert_eq!(
+            Some(TrueColor::new(0xfd, 0xf6, 0xe3)),
+            Palette::solarized_light().background()
+        );
+
+        let nord = Palette::nord();
+        assert_eq!(TrueColor::new(0x88, 0xc0, 0xd0), nord.color(Color::Cyan));
+        // the color cube and the grays are the ones of xterm
+        assert_eq!(Palette::xterm().index(100), nord.index(100));
+    }
+
+    #[test]
+    fn nearest() {
+        let xterm = Palette::xterm();
+        assert_eq!(Color::Red, xterm.nearest_color(TrueColor::new(200, 10, 10)));
+        assert_eq!(
+            Color::BrightWhite,
+            xterm.nearest_color(TrueColor::new(250, 250, 250))
+        );
+        assert_eq!(208, xterm.nearest_index(TrueColor::new(255, 135, 0)));
+        assert_eq!(244, xterm.nearest_index(TrueColor::new(130, 130, 130)));
+
+        let solarized = Palette::solarized_dark();
+        assert_eq!(
+            Color::Blue,
+            solarized.nearest_color(TrueColor::new(0x26, 0x8b, 0xd2))
+        );
+    }
+
+    #[test]
+    fn changes() {
+        let mut palette = Palette::default();
+        palette.set_color(Color::Red, TrueColor::new(1, 2, 3));
+        palette.set_index(200, TrueColor::new(4, 5, 6));
+
+        assert_eq!(TrueColor::new(1, 2, 3), palette.index(1));
+        assert_eq!(
+            TrueColor::new(4, 5, 6),
+            palette.resolve(AllColor::Palette(200))
+        );
+        assert_eq!(
+            TrueColor::new(7, 8, 9),
+            palette.resolve(AllColor::True(TrueColor::new(7, 8, 9)))
+        );
+    }
+}
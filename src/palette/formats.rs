@@ -0,0 +1,995 @@
+//! The files of color schemes of terminals, read and written as palettes.
+
+use std::error::Error;
+use std::fmt;
+use std::iter::Peekable;
+use std::path::Path;
+use std::str::CharIndices;
+
+use super::{Palette, XTERM_COLORS};
+use color::{Color, TrueColor};
+use terminal::parse_rgb_spec;
+
+/// The names of the 16 colors in the Windows Terminal and Alacritty schemes, from black to
+/// bright white, without their `bright` prefix.
+const WINDOWS_TERMINAL_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "purple", "cyan", "white",
+];
+const ALACRITTY_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// The base16 colors of the 16 colors, from black to bright white.
+const BASE16_COLORS: [u8; 16] = [
+    0x00, 0x08, 0x0b, 0x0a, 0x0d, 0x0e, 0x0c, 0x05, 0x03, 0x08, 0x0b, 0x0a, 0x0d, 0x0e, 0x0c, 0x07,
+];
+
+/// A file format of terminal color schemes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// The `.itermcolors` property lists of iTerm2.
+    ITerm2,
+    /// A scheme of the Windows Terminal settings, in JSON: the scheme object alone, or the whole
+    /// settings when their `schemes` list holds only that one.
+    WindowsTerminal,
+    /// A base16 scheme, in YAML, its 16 colors being mapped like base16-shell does. Bright
+    /// colors are the normal ones, except bright black and bright white.
+    Base16,
+    /// The `colorN`, `foreground` and `background` resources of X11 terminals.
+    Xresources,
+    /// The `[colors]` tables of the TOML configuration of Alacritty.
+    Alacritty,
+}
+
+impl Format {
+    /// The format of a file, from its extension or its name.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Format> {
+        let path = path.as_ref();
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_lowercase);
+        match extension.as_deref() {
+            Some("itermcolors") => Some(Format::ITerm2),
+            Some("json") => Some(Format::WindowsTerminal),
+            Some("yaml") | Some("yml") => Some(Format::Base16),
+            Some("toml") => Some(Format::Alacritty),
+            Some("xresources") | Some("xdefaults") => Some(Format::Xresources),
+            _ if name == ".xresources" || name == ".xdefaults" => Some(Format::Xresources),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Format::ITerm2 => "iTerm2",
+            Format::WindowsTerminal => "Windows Terminal",
+            Format::Base16 => "base16",
+            Format::Xresources => "Xresources",
+            Format::Alacritty => "Alacritty",
+        })
+    }
+}
+
+/// A color scheme that couldn't be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportError {
+    format: Format,
+    reason: String,
+}
+
+impl ImportError {
+    pub fn format(&self) -> Format {
+        self.format
+    }
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid {} color scheme: {}", self.format, self.reason)
+    }
+}
+
+impl Error for ImportError {}
+
+/// `"#rrggbb"`, `"#rgb"` or `"0xrrggbb"`.
+fn hex_color(value: &str) -> Option<TrueColor> {
+    let digits = value
+        .strip_prefix('#')
+        .or_else(|| value.strip_prefix("0x"))
+        .or_else(|| value.strip_prefix("0X"))?;
+    format!("#{}", digits).parse().ok()
+}
+
+fn invalid_color(value: &str) -> String {
+    format!("invalid color {:?}", value)
+}
+
+/// The value without its quotes, and what follows them.
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    match value.chars().next() {
+        Some(quote) if quote == '"' || quote == '\'' => {
+            let rest = &value[1..];
+            rest.find(quote).map_or(rest, |end| &rest[..end])
+        }
+        _ => value,
+    }
+}
+
+/// The line without its comment, a `#` out of quotes.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (idx, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, '#') => return &line[..idx],
+            _ => (),
+        }
+    }
+    line
+}
+
+/// The elements of a property list that matter here.
+#[derive(Debug, PartialEq)]
+enum PlistItem<'a> {
+    DictStart,
+    DictEnd,
+    /// A `<key>`, `<real>`, `<integer>` or `<string>` element, with its text.
+    Value(&'a str, &'a str),
+}
+
+fn plist_items(text: &str) -> Result<Vec<PlistItem<'_>>, String> {
+    let mut items = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            let end = comment.find("-->").ok_or("unterminated comment")?;
+            rest = &comment[end + 3..];
+            continue;
+        }
+        let end = rest.find('>').ok_or("unterminated tag")?;
+        let tag = rest[1..end].trim();
+        rest = &rest[end + 1..];
+        match tag {
+            "dict" => items.push(PlistItem::DictStart),
+            "/dict" => items.push(PlistItem::DictEnd),
+            "key" | "real" | "integer" | "string" => {
+                let close = format!("</{}>", tag);
+                let end = rest
+                    .find(&close)
+                    .ok_or_else(|| format!("unterminated <{}>", tag))?;
+                items.push(PlistItem::Value(tag, rest[..end].trim()));
+                rest = &rest[end + close.len()..];
+            }
+            _ => (),
+        }
+    }
+    Ok(items)
+}
+
+/// The color of a `<dict>` of iTerm2, from its components between 0 and 1.
+fn iterm2_color(items: &[PlistItem]) -> Result<TrueColor, String> {
+    let component = |name: &str| -> Result<u8, String> {
+        let value = items
+            .windows(2)
+            .find_map(|pair| match (&pair[0], &pair[1]) {
+                (&PlistItem::Value("key", key), &PlistItem::Value(kind, value))
+                    if key == name && (kind == "real" || kind == "integer") =>
+                {
+                    Some(value)
+                }
+                _ => None,
+            })
+            .ok_or_else(|| format!("missing {}", name))?;
+        let value: f64 = value
+            .parse()
+            .map_err(|_| format!("invalid {} {:?}", name, value))?;
+        Ok((value.clamp(0.0, 1.0) * 255.0).round() as u8)
+    };
+    Ok(TrueColor::new(
+        component("Red Component")?,
+        component("Green Component")?,
+        component("Blue Component")?,
+    ))
+}
+
+fn import_iterm2(palette: &mut Palette, text: &str) -> Result<(), String> {
+    let items = plist_items(text)?;
+    let mut depth = 0;
+    let mut key = None;
+    let mut idx = 0;
+    while idx < items.len() {
+        match items[idx] {
+            PlistItem::DictStart if depth == 1 => {
+                let len = items[idx..]
+                    .iter()
+                    .position(|item| *item == PlistItem::DictEnd)
+                    .ok_or("unterminated <dict>")?;
+                let dict = &items[idx + 1..idx + len];
+                if let Some(key) = key.take() {
+                    set_iterm2_color(palette, key, dict)?;
+                }
+                idx += len;
+            }
+            PlistItem::DictStart => depth += 1,
+            PlistItem::DictEnd => depth -= 1,
+            PlistItem::Value("key", name) if depth == 1 => key = Some(name),
+            PlistItem::Value(..) => key = None,
+        }
+        idx += 1;
+    }
+    Ok(())
+}
+
+fn set_iterm2_color(palette: &mut Palette, key: &str, dict: &[PlistItem]) -> Result<(), String> {
+    let index = key
+        .strip_prefix("Ansi ")
+        .and_then(|key| key.strip_suffix(" Color"))
+        .and_then(|index| index.parse::<u8>().ok());
+    let color = || iterm2_color(dict).map_err(|reason| format!("{}: {}", key, reason));
+    match (key, index) {
+        (_, Some(index)) if index < 16 => palette.set_index(index, color()?),
+        ("Foreground Color", _) => palette.set_foreground(color()?),
+        ("Background Color", _) => palette.set_background(color()?),
+        _ => (),
+    }
+    Ok(())
+}
+
+/// A JSON value, the numbers, booleans and nulls being of no use here.
+#[derive(Debug, PartialEq)]
+enum Json {
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+    Other,
+}
+
+/// A parser of JSON, with the comments and the trailing commas allowed in the Windows Terminal
+/// settings.
+struct JsonParser<'a> {
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> JsonParser<'a> {
+    fn parse(text: &str) -> Result<Json, String> {
+        let mut parser = JsonParser {
+            chars: text.char_indices().peekable(),
+        };
+        let value = parser.value()?;
+        parser.skip_blanks()?;
+        match parser.chars.next() {
+            Some((pos, c)) => Err(format!("unexpected {:?} at position {}", c, pos)),
+            None => Ok(value),
+        }
+    }
+
+    fn unexpected(next: Option<(usize, char)>) -> String {
+        match next {
+            Some((pos, c)) => format!("unexpected {:?} at position {}", c, pos),
+            None => String::from("unexpected end of the document"),
+        }
+    }
+
+    fn skip_blanks(&mut self) -> Result<(), String> {
+        loop {
+            match self.chars.peek() {
+                Some(&(_, c)) if c.is_whitespace() => {
+                    self.chars.next();
+                }
+                Some(&(_, '/')) => {
+                    let slash = self.chars.next();
+                    match self.chars.next() {
+                        Some((_, '/')) => {
+                            while self.chars.next_if(|&(_, c)| c != '\n').is_some() {}
+                        }
+                        Some((_, '*')) => {
+                            let mut star = false;
+                            loop {
+                                match self.chars.next() {
+                                    Some((_, '/')) if star => break,
+                                    Some((_, c)) => star = c == '*',
+                                    None => return Err(String::from("unterminated comment")),
+                                }
+                            }
+                        }
+                        _ => return Err(JsonParser::unexpected(slash)),
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// Skips the blanks and `c`, if it is next.
+    fn eat(&mut self, c: char) -> Result<bool, String> {
+        self.skip_blanks()?;
+        Ok(self.chars.next_if(|&(_, next)| next == c).is_some())
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.eat(c)? {
+            Ok(())
+        } else {
+            Err(JsonParser::unexpected(self.chars.next()))
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_blanks()?;
+        match self.chars.next() {
+            Some((_, '"')) => self.string().map(Json::String),
+            Some((_, '[')) => {
+                let mut items = Vec::new();
+                while !self.eat(']')? {
+                    items.push(self.value()?);
+                    if !self.eat(',')? {
+                        self.expect(']')?;
+                        break;
+                    }
+                }
+                Ok(Json::Array(items))
+            }
+            Some((_, '{')) => {
+                let mut fields = Vec::new();
+                while !self.eat('}')? {
+                    self.expect('"')?;
+                    let key = self.string()?;
+                    self.expect(':')?;
+                    fields.push((key, self.value()?));
+                    if !self.eat(',')? {
+                        self.expect('}')?;
+                        break;
+                    }
+                }
+                Ok(Json::Object(fields))
+            }
+            Some((_, c)) if c == '-' || c.is_ascii_alphanumeric() => {
+                let literal = |&(_, c): &(usize, char)| {
+                    c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '+'
+                };
+                while self.chars.next_if(literal).is_some() {}
+                Ok(Json::Other)
+            }
+            next => Err(JsonParser::unexpected(next)),
+        }
+    }
+
+    /// The rest of a string, after its opening quote.
+    fn string(&mut self) -> Result<String, String> {
+        let mut string = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => return Ok(string),
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, 'n')) => string.push('\n'),
+                    Some((_, 't')) => string.push('\t'),
+                    Some((_, 'r')) => string.push('\r'),
+                    Some((_, 'b')) => string.push('\u{8}'),
+                    Some((_, 'f')) => string.push('\u{c}'),
+                    Some((pos, 'u')) => {
+                        let hex: String = (0..4)
+                            .filter_map(|_| self.chars.next())
+                            .map(|c| c.1)
+                            .collect();
+                        let code = u32::from_str_radix(&hex, 16)
+                            .map_err(|_| format!("invalid escape at position {}", pos - 1))?;
+                        string.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                    }
+                    Some((_, c)) => string.push(c),
+                    None => break,
+                },
+                Some((_, c)) => string.push(c),
+                None => break,
+            }
+        }
+        Err(String::from("unterminated string"))
+    }
+}
+
+/// The scheme of a Windows Terminal document: a scheme, a list of schemes, or the settings
+/// with their `schemes`, which must hold only one.
+fn windows_terminal_scheme(document: &Json) -> Result<&[(String, Json)], String> {
+    let schemes = match *document {
+        Json::Object(ref fields) => match fields.iter().find(|field| field.0 == "schemes") {
+            Some(&(_, Json::Array(ref schemes))) => schemes,
+            Some(_) => return Err(String::from("`schemes` is not a list")),
+            None => return Ok(fields),
+        },
+        Json::Array(ref schemes) => schemes,
+        _ => return Err(String::from("not a JSON object")),
+    };
+    let mut objects = schemes.iter().filter_map(|scheme| match *scheme {
+        Json::Object(ref fields) => Some(fields.as_slice()),
+        _ => None,
+    });
+    match (objects.next(), objects.count()) {
+        (Some(scheme), 0) => Ok(scheme),
+        (None, _) => Err(String::from("no scheme")),
+        (Some(_), others) => Err(format!(
+            "{} schemes, keep only the one to import",
+            others + 1
+        )),
+    }
+}
+
+fn import_windows_terminal(palette: &mut Palette, text: &str) -> Result<(), String> {
+    let document = JsonParser::parse(text)?;
+    for (key, value) in windows_terminal_scheme(&document)? {
+        let (name, bright) = match key.strip_prefix("bright") {
+            Some(name) => (name.to_lowercase(), 8),
+            None => (key.clone(), 0),
+        };
+        let index = WINDOWS_TERMINAL_NAMES.iter().position(|&n| n == name);
+        if index.is_none() && key != "foreground" && key != "background" {
+            continue;
+        }
+        let color = match *value {
+            Json::String(ref value) => hex_color(value).ok_or_else(|| invalid_color(value))?,
+            _ => return Err(format!("{} is not a color", key)),
+        };
+        match index {
+            Some(index) => palette.set_index(index as u8 + bright, color),
+            None if key == "foreground" => palette.set_foreground(color),
+            None => palette.set_background(color),
+        }
+    }
+    Ok(())
+}
+
+fn import_base16(palette: &mut Palette, text: &str) -> Result<(), String> {
+    let mut bases = [None; 16];
+    for line in text.lines() {
+        let line = strip_comment(line);
+        let (key, value) = match line.split_once(':') {
+            Some(pair) => pair,
+            None => continue,
+        };
+        let base = key
+            .trim()
+            .strip_prefix("base0")
+            .filter(|digit| digit.len() == 1)
+            .and_then(|digit| u8::from_str_radix(digit, 16).ok());
+        if let Some(base) = base {
+            let value = unquote(value);
+            let color = hex_color(value)
+                .or_else(|| hex_color(&format!("#{}", value)))
+                .ok_or_else(|| invalid_color(value))?;
+            bases[usize::from(base)] = Some(color);
+        }
+    }
+
+    let base =
+        |base: u8| bases[usize::from(base)].ok_or_else(|| format!("missing base0{:X}", base));
+    for (index, &b) in BASE16_COLORS.iter().enumerate() {
+        palette.set_index(index as u8, base(b)?);
+    }
+    palette.set_foreground(base(0x05)?);
+    palette.set_background(base(0x00)?);
+    Ok(())
+}
+
+fn import_xresources(palette: &mut Palette, text: &str) -> Result<(), String> {
+    let mut macros = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(define) = line.strip_prefix("#define") {
+            let mut parts = define.split_whitespace();
+            if let (Some(name), Some(value)) = (parts.next(), parts.next()) {
+                macros.push((name, value));
+            }
+            continue;
+        }
+        if line.starts_with('!') || line.starts_with('#') {
+            continue;
+        }
+        let (resource, value) = match line.split_once(':') {
+            Some(pair) => pair,
+            None => continue,
+        };
+        let name = resource.rsplit(['.', '*']).next().unwrap_or("").trim();
+        let index = name
+            .strip_prefix("color")
+            .and_then(|index| index.parse::<u8>().ok());
+        if index.is_none() && name != "foreground" && name != "background" {
+            continue;
+        }
+
+        let value = value.trim();
+        let value = macros
+            .iter()
+            .rev()
+            .find(|&&(name, _)| name == value)
+            .map_or(value, |&(_, value)| value);
+        let color = hex_color(value)
+            .or_else(|| parse_rgb_spec(value))
+            .ok_or_else(|| invalid_color(value))?;
+        match index {
+            Some(index) => palette.set_index(index, color),
+            None if name == "foreground" => palette.set_foreground(color),
+            None => palette.set_background(color),
+        }
+    }
+    Ok(())
+}
+
+/// The `key = value` pairs of `text`, a line or the inside of an inline table, with their
+/// dotted keys and the keys of their inline tables appended to `table`.
+fn toml_pairs<'a>(table: &str, text: &'a str, pairs: &mut Vec<(String, &'a str)>) {
+    let mut items = Vec::new();
+    let (mut quote, mut depth, mut start) = (None, 0, 0);
+    for (idx, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, '{') | (None, '[') => depth += 1,
+            (None, '}') | (None, ']') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                items.push(&text[start..idx]);
+                start = idx + 1;
+            }
+            _ => (),
+        }
+    }
+    items.push(&text[start..]);
+    for item in items {
+        let (key, value) = match item.split_once('=') {
+            Some((key, value)) => (key, value.trim()),
+            None => continue,
+        };
+        let mut path = String::from(table);
+        for part in key.split('.') {
+            if !path.is_empty() {
+                path.push('.');
+            }
+            path.push_str(unquote(part));
+        }
+        match value.strip_prefix('{') {
+            Some(inner) => toml_pairs(&path, inner.trim_end_matches('}'), pairs),
+            None => pairs.push((path, unquote(value))),
+        }
+    }
+}
+
+fn import_alacritty(palette: &mut Palette, text: &str) -> Result<(), String> {
+    let mut table = String::new();
+    let mut pairs = Vec::new();
+    for line in text.lines() {
+        let line = strip_comment(line).trim();
+        if let Some(header) = line.strip_prefix('[') {
+            table = header.trim_end_matches(']').replace(' ', "");
+            continue;
+        }
+        toml_pairs(&table, line, &mut pairs);
+    }
+    for (path, value) in pairs {
+        let (table, key) = path.rsplit_once('.').unwrap_or(("", &path));
+        let index = ALACRITTY_NAMES.iter().position(|&name| name == key);
+        let color = || hex_color(value).ok_or_else(|| invalid_color(value));
+        match (table, index) {
+            ("colors.normal", Some(index)) => palette.set_index(index as u8, color()?),
+            ("colors.bright", Some(index)) => palette.set_index(index as u8 + 8, color()?),
+            ("colors.primary", None) if key == "foreground" => palette.set_foreground(color()?),
+            ("colors.primary", None) if key == "background" => palette.set_background(color()?),
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+/// The color between `a` and `b`, at `ratio` of the way.
+fn blend(a: TrueColor, b: TrueColor, ratio: f64) -> TrueColor {
+    let channel =
+        |x: u8, y: u8| (f64::from(x) + (f64::from(y) - f64::from(x)) * ratio).round() as u8;
+    TrueColor::new(
+        channel(a.r(), b.r()),
+        channel(a.g(), b.g()),
+        channel(a.b(), b.b()),
+    )
+}
+
+/// `"rrggbb"`, as in base16 schemes.
+fn bare_hex(color: TrueColor) -> String {
+    color.to_string()[1..].to_string()
+}
+
+impl Palette {
+    /// Reads a color scheme file of the given format: its 16 colors, and its default
+    /// foreground and background colors. The colors the scheme doesn't set keep their xterm
+    /// value, and the default colors stay unknown.
+    ///
+    /// ```
+    /// use colored::*;
+    /// use colored::palette::{Format, Palette};
+    ///
+    /// let scheme = "
+    ///     [colors.primary]
+    ///     background = '#282a36'
+    ///
+    ///     [colors.normal]
+    ///     red = '#ff5555'
+    /// ";
+    /// let palette = Palette::import(scheme, Format::Alacritty).unwrap();
+    /// assert_eq!(TrueColor::new(0xff, 0x55, 0x55), palette.color(Color::Red));
+    /// assert_eq!(Some(TrueColor::new(0x28, 0x2a, 0x36)), palette.background());
+    /// ```
+    pub fn import(text: &str, format: Format) -> Result<Palette, ImportError> {
+        let mut palette = Palette::with_colors(XTERM_COLORS);
+        let imported = match format {
+            Format::ITerm2 => import_iterm2(&mut palette, text),
+            Format::WindowsTerminal => import_windows_terminal(&mut palette, text),
+            Format::Base16 => import_base16(&mut palette, text),
+            Format::Xresources => import_xresources(&mut palette, text),
+            Format::Alacritty => import_alacritty(&mut palette, text),
+        };
+        let found = palette != Palette::with_colors(XTERM_COLORS)
+            || palette.foreground.is_some()
+            || palette.background.is_some();
+        match imported {
+            Ok(()) if found => Ok(palette),
+            Ok(()) => Err(String::from("no color found")),
+            Err(reason) => Err(reason),
+        }
+        .map_err(|reason| ImportError { format, reason })
+    }
+
+    /// Writes the 16 colors and the default colors as a color scheme file of the given format,
+    /// under the given name. The unknown default colors are white and black.
+    ///
+    /// base16 has no bright colors: bright black and bright white are written, and the other
+    /// base16 colors are blends of the ones of the palette.
+    pub fn export(&self, format: Format, name: &str) -> String {
+        let foreground = self.foreground.unwrap_or(self.color(Color::White));
+        let background = self.background.unwrap_or(self.color(Color::Black));
+        let colors = &self.colors[..16];
+        let mut out = String::new();
+        match format {
+            Format::ITerm2 => {
+                out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+                out.push_str(
+                    "<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \
+                     \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n",
+                );
+                out.push_str(&format!("<!-- {} -->\n", name.replace("--", "- -")));
+                out.push_str("<plist version=\"1.0\">\n<dict>\n");
+                let named = colors
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, &color)| (format!("Ansi {} Color", idx), color))
+                    .chain(vec![
+                        (String::from("Background Color"), background),
+                        (String::from("Foreground Color"), foreground),
+                    ]);
+                for (key, color) in named {
+                    out.push_str(&format!("\t<key>{}</key>\n\t<dict>\n", key));
+                    for &(component, value) in &[
+                        ("Blue", color.b()),
+                        ("Green", color.g()),
+                        ("Red", color.r()),
+                    ] {
+                        out.push_str(&format!(
+                            "\t\t<key>{} Component</key>\n\t\t<real>{:.6}</real>\n",
+                            component,
+                            f64::from(value) / 255.0
+                        ));
+                    }
+                    out.push_str("\t\t<key>Color Space</key>\n\t\t<string>sRGB</string>\n");
+                    out.push_str("\t</dict>\n");
+                }
+                out.push_str("</dict>\n</plist>\n");
+            }
+            Format::WindowsTerminal => {
+                let hex = |color: TrueColor| color.to_string().to_uppercase();
+                let escaped = name.replace('\\', "\\\\").replace('"', "\\\"");
+                out.push_str(&format!("{{\n    \"name\": \"{}\",\n", escaped));
+                out.push_str(&format!("    \"foreground\": \"{}\",\n", hex(foreground)));
+                out.push_str(&format!("    \"background\": \"{}\",\n", hex(background)));
+                for (idx, &color) in colors.iter().enumerate() {
+                    let base = WINDOWS_TERMINAL_NAMES[idx % 8];
+                    let key = if idx < 8 {
+                        String::from(base)
+                    } else {
+                        format!("bright{}{}", base[..1].to_uppercase(), &base[1..])
+                    };
+                    let separator = if idx < 15 { "," } else { "" };
+                    out.push_str(&format!(
+                        "    \"{}\": \"{}\"{}\n",
+                        key,
+                        hex(color),
+                        separator
+                    ));
+                }
+                out.push_str("}\n");
+            }
+            Format::Base16 => {
+                let mut bases = [TrueColor::new(0, 0, 0); 16];
+                // the normal colors rather than the bright ones
+                for (&base, &color) in BASE16_COLORS.iter().zip(colors).rev() {
+                    bases[usize::from(base)] = color;
+                }
+                bases[0x00] = background;
+                bases[0x05] = foreground;
+                bases[0x01] = blend(bases[0x00], bases[0x03], 1.0 / 3.0);
+                bases[0x02] = blend(bases[0x00], bases[0x03], 2.0 / 3.0);
+                bases[0x04] = blend(bases[0x03], bases[0x05], 0.5);
+                bases[0x06] = blend(bases[0x05], bases[0x07], 0.5);
+                bases[0x09] = blend(bases[0x08], bases[0x0a], 0.5);
+                bases[0x0f] = blend(bases[0x08], bases[0x00], 0.5);
+
+                out.push_str(&format!("scheme: {:?}\nauthor: \"\"\n", name));
+                for (base, &color) in bases.iter().enumerate() {
+                    out.push_str(&format!("base0{:X}: \"{}\"\n", base, bare_hex(color)));
+                }
+            }
+            Format::Xresources => {
+                out.push_str(&format!("! {}\n", name));
+                out.push_str(&format!("*.foreground: {}\n", foreground));
+                out.push_str(&format!("*.background: {}\n", background));
+                for (idx, color) in colors.iter().enumerate() {
+                    out.push_str(&format!("*.color{}: {}\n", idx, color));
+                }
+            }
+            Format::Alacritty => {
+                out.push_str(&format!("# {}\n\n[colors.primary]\n", name));
+                out.push_str(&format!("background = '{}'\n", background));
+                out.push_str(&format!("foreground = '{}'\n", foreground));
+                for (table, colors) in &[("normal", &colors[..8]), ("bright", &colors[8..])] {
+                    out.push_str(&format!("\n[colors.{}]\n", table));
+                    for (name, color) in ALACRITTY_NAMES.iter().zip(colors.iter()) {
+                        out.push_str(&format!("{} = '{}'\n", name, color));
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Format; 5] = [
+        Format::ITerm2,
+        Format::WindowsTerminal,
+        Format::Base16,
+        Format::Xresources,
+        Format::Alacritty,
+    ];
+
+    #[test]
+    fn iterm2() {
+        let scheme = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+	<key>Ansi 1 Color</key>
+	<dict>
+		<key>Alpha Component</key>
+		<real>1</real>
+		<key>Blue Component</key>
+		<real>0.18431372940540314</real>
+		<key>Color Space</key>
+		<string>sRGB</string>
+		<key>Green Component</key>
+		<real>0.19607843458652496</real>
+		<key>Red Component</key>
+		<real>0.86274510622024536</real>
+	</dict>
+	<key>Background Color</key>
+	<dict>
+		<key>Blue Component</key>
+		<integer>0</integer>
+		<key>Green Component</key>
+		<real>0.16862745583057404</real>
+		<key>Red Component</key>
+		<real>0.0</real>
+	</dict>
+	<key>Selection Color</key>
+	<dict>
+		<key>Blue Component</key>
+		<real>1</real>
+		<key>Green Component</key>
+		<real>1</real>
+		<key>Red Component</key>
+		<real>1</real>
+	</dict>
+</dict>
+</plist>"#;
+        let palette = Palette::import(scheme, Format::ITerm2).unwrap();
+        assert_eq!(TrueColor::new(0xdc, 0x32, 0x2f), palette.color(Color::Red));
+        assert_eq!(Some(TrueColor::new(0, 0x2b, 0)), palette.background());
+        assert_eq!(None, palette.foreground());
+        assert_eq!(XTERM_COLORS[2], palette.color(Color::Green));
+
+        let missing = "<dict><key>Ansi 0 Color</key><dict><key>Red Component</key>\
+                       <real>1</real></dict></dict>";
+        assert_eq!(
+            "invalid iTerm2 color scheme: Ansi 0 Color: missing Green Component",
+            Palette::import(missing, Format::ITerm2)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn windows_terminal() {
+        let scheme = r##"{
+            "name": "Campbell",
+            "cursorColor": "#FFFFFF",
+            "foreground": "#CCCCCC",
+            "background": "#0C0C0C",
+            "purple": "#881798",
+            "brightBlue": "#3B78FF"
+        }"##;
+        let palette = Palette::import(scheme, Format::WindowsTerminal).unwrap();
+        assert_eq!(
+            TrueColor::new(0x88, 0x17, 0x98),
+            palette.color(Color::Magenta)
+        );
+        assert_eq!(
+            TrueColor::new(0x3b, 0x78, 0xff),
+            palette.color(Color::BrightBlue)
+        );
+        assert_eq!(Some(TrueColor::new(0xcc, 0xcc, 0xcc)), palette.foreground());
+
+        let err = Palette::import(r#"{"red": "crimson"}"#, Format::WindowsTerminal).unwrap_err();
+        assert_eq!(Format::WindowsTerminal, err.format());
+        assert!(Palette::import(r#"{"name": "empty"}"#, Format::WindowsTerminal).is_err());
+        assert!(Palette::import(r#"{"red": 3}"#, Format::WindowsTerminal).is_err());
+    }
+
+    #[test]
+    fn windows_terminal_settings() {
+        let settings = r##"{
+            // the profiles have colors too, which are not the scheme
+            "profiles": { "defaults": { "background": "#FF0000" } },
+            "schemes": [
+                {
+                    "name": "One \"Half\"",
+                    "background": "#282C34", /* the page */
+                    "red": "#E06C75",
+                },
+            ],
+        }"##;
+        let palette = Palette::import(settings, Format::WindowsTerminal).unwrap();
+        assert_eq!(Some(TrueColor::new(0x28, 0x2c, 0x34)), palette.background());
+        assert_eq!(TrueColor::new(0xe0, 0x6c, 0x75), palette.color(Color::Red));
+
+        let two = r##"{"schemes": [{"red": "#E06C75"}, {"red": "#C50F1F"}]}"##;
+        let err = Palette::import(two, Format::WindowsTerminal).unwrap_err();
+        assert!(err.to_string().contains("2 schemes"), "{}", err);
+        assert!(Palette::import("{\"red\": \"#E06C75\"", Format::WindowsTerminal).is_err());
+    }
+
+    #[test]
+    fn base16() {
+        let mut scheme = String::from("scheme: \"Test\" # a comment\nauthor: \"me\"\n");
+        for base in 0..16 {
+            scheme.push_str(&format!("base0{:X}: \"{:02x}0000\"\n", base, base));
+        }
+        let palette = Palette::import(&scheme, Format::Base16).unwrap();
+        assert_eq!(TrueColor::new(0x08, 0, 0), palette.color(Color::Red));
+        assert_eq!(TrueColor::new(0x08, 0, 0), palette.color(Color::BrightRed));
+        assert_eq!(
+            TrueColor::new(0x03, 0, 0),
+            palette.color(Color::BrightBlack)
+        );
+        assert_eq!(Some(TrueColor::new(0x05, 0, 0)), palette.foreground());
+        // the 256 colors are the ones of xterm
+        assert_eq!(Palette::xterm().index(100), palette.index(100));
+
+        let err = Palette::import("base00: \"000000\"", Format::Base16).unwrap_err();
+        assert_eq!(
+            "invalid base16 color scheme: missing base08",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn xresources() {
+        let scheme = "! Solarized\n\
+                      #define S_base03 #002b36\n\
+                      *background: S_base03\n\
+                      URxvt*color1: #dc322f\n\
+                      XTerm*vt100.color12: rgb:83/94/96\n\
+                      *.color200: #010203\n\
+                      *.font: monospace\n";
+        let palette = Palette::import(scheme, Format::Xresources).unwrap();
+        assert_eq!(Some(TrueColor::new(0, 0x2b, 0x36)), palette.background());
+        assert_eq!(TrueColor::new(0xdc, 0x32, 0x2f), palette.color(Color::Red));
+        assert_eq!(
+            TrueColor::new(0x83, 0x94, 0x96),
+            palette.color(Color::BrightBlue)
+        );
+        assert_eq!(TrueColor::new(1, 2, 3), palette.index(200));
+
+        assert!(Palette::import("*.color1: crimson", Format::Xresources).is_err());
+    }
+
+    #[test]
+    fn alacritty() {
+        let scheme = "# Dracula\n\
+                      [colors.primary]\n\
+                      foreground = \"#f8f8f2\" # the text\n\
+                      \n\
+                      [colors.bright]\n\
+                      magenta = '0xff92df'\n\
+                      \n\
+                      [colors.cursor]\n\
+                      text = '#000000'\n";
+        let palette = Palette::import(scheme, Format::Alacritty).unwrap();
+        assert_eq!(Some(TrueColor::new(0xf8, 0xf8, 0xf2)), palette.foreground());
+        assert_eq!(
+            TrueColor::new(0xff, 0x92, 0xdf),
+            palette.color(Color::BrightMagenta)
+        );
+        assert_eq!(XTERM_COLORS[0], palette.color(Color::Black));
+    }
+
+    #[test]
+    fn alacritty_inline_tables() {
+        let scheme = "[colors]\n\
+                      normal = { red = '#ff5555', green = \"#50fa7b\" }\n\
+                      bright.blue = '#d6acff'\n\
+                      primary = { foreground = '#f8f8f2', background = '#282a36' }\n\
+                      cursor = { text = 'CellBackground', cursor = 'CellForeground' }\n";
+        let palette = Palette::import(scheme, Format::Alacritty).unwrap();
+        assert_eq!(TrueColor::new(0xff, 0x55, 0x55), palette.color(Color::Red));
+        assert_eq!(
+            TrueColor::new(0x50, 0xfa, 0x7b),
+            palette.color(Color::Green)
+        );
+        assert_eq!(
+            TrueColor::new(0xd6, 0xac, 0xff),
+            palette.color(Color::BrightBlue)
+        );
+        assert_eq!(Some(TrueColor::new(0x28, 0x2a, 0x36)), palette.background());
+
+        let nested = "colors = { normal = { black = '#21222c' } }";
+        let palette = Palette::import(nested, Format::Alacritty).unwrap();
+        assert_eq!(
+            TrueColor::new(0x21, 0x22, 0x2c),
+            palette.color(Color::Black)
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        for &format in &ALL {
+            for palette in &[Palette::dracula(), Palette::solarized_light()] {
+                let exported = palette.export(format, "My \"scheme\"");
+                let imported = Palette::import(&exported, format).unwrap();
+                if format == Format::Base16 {
+                    assert_eq!(palette.color(Color::Red), imported.color(Color::Red));
+                    assert_eq!(palette.background(), imported.background());
+                    assert_eq!(palette.foreground(), imported.foreground());
+                } else {
+                    assert_eq!(*palette, imported, "{}", format);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn formats_of_paths() {
+        assert_eq!(
+            Some(Format::ITerm2),
+            Format::from_path("Solarized Dark.itermcolors")
+        );
+        assert_eq!(
+            Some(Format::Xresources),
+            Format::from_path("/home/me/.Xresources")
+        );
+        assert_eq!(
+            Some(Format::Alacritty),
+            Format::from_path("themes/dracula.toml")
+        );
+        assert_eq!(None, Format::from_path("notes.txt"));
+    }
+}
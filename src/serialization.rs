@@ -0,0 +1,490 @@
+//! `Serialize` and `Deserialize` implementations, behind the `serde` feature.
+//!
+//! Colors and styles use the same strings as their `FromStr` implementations (`"bright red"`,
+//! `"#abcdef"`, `"196"`, `"bold"`...), and a `ColoredStyle` is a map with optional `fg`, `bg`,
+//! `underline_color` and `styles` keys:
+//!
+//! ```toml
+//! [theme]
+//! error = { fg = "bright red", styles = ["bold"] }
+//! path = { fg = "#5f87ff", bg = 236, styles = ["underline"] }
+//! ```
+//!
+//! Errors name the path of the invalid value, like `error.styles[1]: unknown style "blod"`.
+
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use color::{AllColor, Color, TrueColor};
+use style::{ColoredStyle, Styles};
+use theme::Theme;
+
+/// Deserializes any `FromStr` type from a string, `what` naming it in the error messages.
+struct FromStrVisitor<T> {
+    what: &'static str,
+    marker: PhantomData<T>,
+}
+
+impl<T> FromStrVisitor<T> {
+    fn new(what: &'static str) -> FromStrVisitor<T> {
+        FromStrVisitor {
+            what,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T: FromStr> Visitor<'de> for FromStrVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a {} name", self.what)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+        v.parse()
+            .map_err(|_| E::custom(format_args!("unknown {} {:?}", self.what, v)))
+    }
+}
+
+macro_rules! impl_serde_from_str {
+    ($type:ty, $what:expr) => {
+        impl Serialize for $type {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $type {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_str(FromStrVisitor::new($what))
+            }
+        }
+    };
+}
+
+impl_serde_from_str!(Color, "color");
+impl_serde_from_str!(TrueColor, "color");
+impl_serde_from_str!(Styles, "style");
+
+/// Palette colors are written as numbers, the other colors as strings.
+impl Serialize for AllColor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            AllColor::Palette(code) => serializer.serialize_u8(code),
+            _ => serializer.collect_str(self),
+        }
+    }
+}
+
+struct AllColorVisitor;
+
+impl<'de> Visitor<'de> for AllColorVisitor {
+    type Value = AllColor;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a color name, an hexadecimal color or a palette index")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<AllColor, E> {
+        FromStrVisitor::new("color").visit_str(v)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<AllColor, E> {
+        if v <= u64::from(u8::MAX) {
+            Ok(AllColor::Palette(v as u8))
+        } else {
+            Err(E::custom(format_args!("palette index {} is out of range", v)))
+        }
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<AllColor, E> {
+        if v < 0 {
+            Err(E::custom(format_args!("palette index {} is out of range", v)))
+        } else {
+            self.visit_u64(v as u64)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for AllColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(AllColorVisitor)
+    }
+}
+
+impl Serialize for ColoredStyle {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let styles = self.styles();
+        let mut map = serializer.serialize_map(None)?;
+
+        if let Some(fg) = self.fgcolor() {
+            map.serialize_entry("fg", &fg)?;
+        }
+        if let Some(bg) = self.bgcolor() {
+            map.serialize_entry("bg", &bg)?;
+        }
+        if let Some(underline) = self.ulcolor() {
+            map.serialize_entry("underline_color", &underline)?;
+        }
+        if !styles.is_empty() {
+            map.serialize_entry("styles", &styles)?;
+        }
+        map.end()
+    }
+}
+
+/// Prefixes an error with the path of the invalid value, like `error.styles[1]`.
+fn at<E: de::Error>(path: &str, err: E) -> E {
+    if path.is_empty() {
+        err
+    } else {
+        E::custom(format_args!("{}: {}", path, err))
+    }
+}
+
+/// The path of the `key` of the map at `path`.
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// Deserializes a value holding no nested value, naming its path in the errors.
+struct Leaf<'a, T> {
+    path: &'a str,
+    marker: PhantomData<T>,
+}
+
+impl<'a, T> Leaf<'a, T> {
+    fn new(path: &'a str) -> Leaf<'a, T> {
+        Leaf {
+            path,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, 'a, T: Deserialize<'de>> DeserializeSeed<'de> for Leaf<'a, T> {
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+        T::deserialize(deserializer).map_err(|e| at(self.path, e))
+    }
+}
+
+/// Names `path` in the errors about the value itself, like a string instead of a map. Once
+/// `entered` is set by the visitor, the errors are about nested values, which already name
+/// their path.
+fn at_unless_entered<E: de::Error>(path: &str, entered: &Cell<bool>, err: E) -> E {
+    if entered.get() {
+        err
+    } else {
+        at(path, err)
+    }
+}
+
+/// A list of styles at `path`, which reports the index of the invalid ones.
+struct StylesSeed<'a> {
+    path: &'a str,
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for StylesSeed<'a> {
+    type Value = Vec<Styles>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Vec<Styles>, D::Error> {
+        struct StylesVisitor<'a> {
+            path: &'a str,
+            entered: &'a Cell<bool>,
+        }
+
+        impl<'de, 'a> Visitor<'de> for StylesVisitor<'a> {
+            type Value = Vec<Styles>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a list of styles")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<Styles>, A::Error> {
+                self.entered.set(true);
+                let mut styles = Vec::new();
+
+                loop {
+                    let path = format!("{}[{}]", self.path, styles.len());
+                    match seq.next_element_seed(Leaf::new(&path))? {
+                        Some(style) => styles.push(style),
+                        None => return Ok(styles),
+                    }
+                }
+            }
+        }
+
+        let entered = Cell::new(false);
+        let visitor = StylesVisitor {
+            path: self.path,
+            entered: &entered,
+        };
+        deserializer
+            .deserialize_seq(visitor)
+            .map_err(|e| at_unless_entered(self.path, &entered, e))
+    }
+}
+
+const STYLE_FIELDS: &[&str] = &["fg", "bg", "underline_color", "styles"];
+
+/// A `ColoredStyle` at `path`.
+struct StyleSeed<'a> {
+    path: &'a str,
+}
+
+struct ColoredStyleVisitor<'a> {
+    path: &'a str,
+    entered: &'a Cell<bool>,
+}
+
+impl<'de, 'a> Visitor<'de> for ColoredStyleVisitor<'a> {
+    type Value = ColoredStyle;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map with optional `fg`, `bg`, `underline_color` and `styles` keys")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<ColoredStyle, A::Error> {
+        self.entered.set(true);
+        let mut style = ColoredStyle::new();
+
+        while let Some(key) = map.next_key::<String>()? {
+            let path = join(self.path, &key);
+            match key.as_str() {
+                "fg" => style = style.fg(map.next_value_seed(Leaf::new(&path))?),
+                "bg" => style = style.bg(map.next_value_seed(Leaf::new(&path))?),
+                "underline_color" => {
+                    style = style.underline_color(map.next_value_seed(Leaf::new(&path))?)
+                }
+                "styles" => {
+                    for s in map.next_value_seed(StylesSeed { path: &path })? {
+                        style = style.with(s);
+                    }
+                }
+                _ => return Err(at(self.path, de::Error::unknown_field(&key, STYLE_FIELDS))),
+            }
+        }
+        Ok(style)
+    }
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for StyleSeed<'a> {
+    type Value = ColoredStyle;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<ColoredStyle, D::Error> {
+        let entered = Cell::new(false);
+        let visitor = ColoredStyleVisitor {
+            path: self.path,
+            entered: &entered,
+        };
+        deserializer
+            .deserialize_map(visitor)
+            .map_err(|e| at_unless_entered(self.path, &entered, e))
+    }
+}
+
+impl<'de> Deserialize<'de> for ColoredStyle {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        StyleSeed { path: "" }.deserialize(deserializer)
+    }
+}
+
+/// The roles are written in alphabetical order.
+impl Serialize for Theme {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let styles: BTreeMap<&str, ColoredStyle> = self
+            .roles()
+            .filter_map(|role| self.get(role).map(|style| (role, style)))
+            .collect();
+        styles.serialize(serializer)
+    }
+}
+
+/// Reads the roles of `theme` from a map of roles to styles.
+struct ThemeVisitor(Theme);
+
+impl<'de> Visitor<'de> for ThemeVisitor {
+    type Value = Theme;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map of roles to styles")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Theme, A::Error> {
+        let mut theme = self.0;
+
+        while let Some(role) = map.next_key::<String>()? {
+            let style = map.next_value_seed(StyleSeed { path: &role })?;
+            theme.set(role, style);
+        }
+        Ok(theme)
+    }
+}
+
+impl<'de> Deserialize<'de> for Theme {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(ThemeVisitor(Theme::new()))
+    }
+}
+
+impl Theme {
+    /// Reads roles from a configuration document, in any format supported by serde, on top of the
+    /// roles of this theme.
+    ///
+    /// # Example
+    /// ```
+    /// # extern crate colored;
+    /// # extern crate toml;
+    /// # fn main() {
+    /// use colored::theme::Theme;
+    ///
+    /// let config = r#"error = { fg = "red", styles = ["bold", "underline"] }"#;
+    /// let theme = Theme::dark()
+    ///     .extend_from(&mut toml::Deserializer::new(config))
+    ///     .unwrap();
+    ///
+    /// // the roles missing from the document are kept
+    /// assert!(theme.get("path").is_some());
+    /// # }
+    /// ```
+    pub fn extend_from<'de, D: Deserializer<'de>>(self, deserializer: D) -> Result<Theme, D::Error> {
+        deserializer.deserialize_map(ThemeVisitor(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate serde_json;
+    extern crate toml;
+
+    use super::*;
+
+    #[test]
+    fn colors_use_parser_strings() {
+        assert_eq!("\"bright red\"", serde_json::to_string(&Color::BrightRed).unwrap());
+        assert_eq!(
+            "\"#abcdef\"",
+            serde_json::to_string(&TrueColor::new(171, 205, 239)).unwrap()
+        );
+        assert_eq!("57", serde_json::to_string(&AllColor::Palette(57)).unwrap());
+        assert_eq!(
+            "\"curly_underline\"",
+            serde_json::to_string(&Styles::CurlyUnderline).unwrap()
+        );
+    }
+
+    #[test]
+    fn all_color_roundtrip() {
+        let colors = [
+            AllColor::Color(Color::Cyan),
+            AllColor::True(TrueColor::new(1, 2, 3)),
+            AllColor::Palette(255),
+        ];
+
+        for color in colors.iter() {
+            let json = serde_json::to_string(color).unwrap();
+            assert_eq!(*color, serde_json::from_str::<AllColor>(&json).unwrap());
+        }
+        assert_eq!(
+            AllColor::Palette(42),
+            serde_json::from_str::<AllColor>("\"42\"").unwrap()
+        );
+    }
+
+    #[test]
+    fn colored_style_roundtrip() {
+        let style = ColoredStyle::new()
+            .color(Color::Red)
+            .on_palette(236)
+            .bold()
+            .with(Styles::CurlyUnderline)
+            .underline_color(AllColor::Palette(1));
+
+        let json = serde_json::to_string(&style).unwrap();
+        assert_eq!(
+            r#"{"fg":"red","bg":236,"underline_color":1,"styles":["bold","curly_underline"]}"#,
+            json
+        );
+        assert_eq!(style, serde_json::from_str(&json).unwrap());
+        assert_eq!(ColoredStyle::new(), serde_json::from_str("{}").unwrap());
+    }
+
+    #[test]
+    fn theme_from_toml() {
+        let config = r##"
+            error = { fg = "bright red", styles = ["bold"] }
+            path = { fg = "#5f87ff", bg = 236 }
+        "##;
+
+        let theme: Theme = toml::from_str(config).unwrap();
+        assert_eq!(
+            Some(ColoredStyle::new().color(Color::BrightRed).bold()),
+            theme.get("error")
+        );
+        assert_eq!(
+            Some(ColoredStyle::new().true_color(0x5f, 0x87, 0xff).on_palette(236)),
+            theme.get("path")
+        );
+        assert_eq!(None, theme.get("hint"));
+    }
+
+    #[test]
+    fn theme_roundtrip() {
+        let json = serde_json::to_string(&Theme::light()).unwrap();
+        assert_eq!(Theme::light(), serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn extend_from_keeps_other_roles() {
+        let mut de = serde_json::Deserializer::from_str(r#"{"hint": {"fg": "green"}}"#);
+        let theme = Theme::dark().extend_from(&mut de).unwrap();
+
+        assert_eq!(Some(ColoredStyle::new().color(Color::Green)), theme.get("hint"));
+        assert_eq!(Theme::dark().get("error"), theme.get("error"));
+    }
+
+    #[test]
+    fn errors_have_key_paths() {
+        let err = serde_json::from_str::<Theme>(r#"{"error": {"fg": "reed"}}"#).unwrap_err();
+        assert!(
+            err.to_string().starts_with("error.fg: unknown color \"reed\""),
+            "{}",
+            err
+        );
+
+        let err = toml::from_str::<Theme>(r#"path = { styles = ["bold", "blod"] }"#).unwrap_err();
+        assert!(
+            err.to_string().starts_with("path.styles[1]: unknown style \"blod\""),
+            "{}",
+            err
+        );
+
+        let err = serde_json::from_str::<Theme>(r#"{"hint": {"fgg": "red"}}"#).unwrap_err();
+        assert!(err.to_string().starts_with("hint: unknown field `fgg`"), "{}", err);
+
+        // the messages of the deserializer itself
+        let err = toml::from_str::<Theme>(r#"error = "red""#).unwrap_err();
+        assert!(err.to_string().starts_with("error: invalid type"), "{}", err);
+        let err = serde_json::from_str::<Theme>(r#"{"a.b": {"styles": "bold"}}"#).unwrap_err();
+        assert!(err.to_string().starts_with("a.b.styles: invalid type"), "{}", err);
+        let err = serde_json::from_str::<ColoredStyle>(r#"{"bg": 256}"#).unwrap_err();
+        assert!(
+            err.to_string().starts_with("bg: palette index 256 is out of range"),
+            "{}",
+            err
+        );
+    }
+}
@@ -0,0 +1,331 @@
+//! Parsing of SGR ("Select Graphic Rendition") parameters, the `1;34` of `"\x1B[1;34m"`, as
+//! found in `LS_COLORS`, `GREP_COLORS` or in the output of this crate.
+
+use std::error::Error;
+use std::fmt;
+use std::str::Split;
+
+use color::{AllColor, Color, TrueColor};
+use style::{ColoredStyle, Styles};
+
+/// SGR parameters that couldn't be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SgrError {
+    params: String,
+    message: String,
+}
+
+impl SgrError {
+    fn new<S: Into<String>>(params: &str, message: S) -> SgrError {
+        SgrError {
+            params: String::from(params),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for SgrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} in SGR parameters {:?}", self.message, self.params)
+    }
+}
+
+impl Error for SgrError {}
+
+const UNDERLINES: [Styles; 5] = [
+    Styles::Underline,
+    Styles::DoubleUnderline,
+    Styles::CurlyUnderline,
+    Styles::DottedUnderline,
+    Styles::DashedUnderline,
+];
+
+impl ColoredStyle {
+    /// Parses SGR parameters, like `"01;34"` or `"38;5;208"`.
+    ///
+    /// # Example
+    /// ```
+    /// # use colored::*;
+    /// let style = ColoredStyle::from_sgr("01;38;5;208").unwrap();
+    /// assert_eq!(style, ColoredStyle::new().bold().palette(208));
+    /// ```
+    pub fn from_sgr(params: &str) -> Result<ColoredStyle, SgrError> {
+        ColoredStyle::new().apply_sgr(params)
+    }
+
+    /// Updates this style the way a terminal does when it receives these SGR parameters: `0`
+    /// resets everything, `22` turns bold off, `39` goes back to the default foreground...
+    pub fn apply_sgr(self, params: &str) -> Result<ColoredStyle, SgrError> {
+        if params.is_empty() {
+            return Ok(ColoredStyle::new());
+        }
+
+        let mut style = self;
+        let mut codes = params.split(';');
+
+        while let Some(code) = codes.next() {
+            // sub-parameters, like the underline style in "4:3"
+            let mut sub = code.split(':');
+            let main = sub.next().unwrap_or("");
+            let value: u8 = if main.is_empty() {
+                0
+            } else {
+                main.parse()
+                    .map_err(|_| SgrError::new(params, format!("invalid code {:?}", code)))?
+            };
+
+            style = match value {
+                0 => ColoredStyle::new(),
+                1 => style.with(Styles::Bold),
+                2 => style.with(Styles::Dimmed),
+                3 => style.with(Styles::Italic),
+                4 => {
+                    let style = UNDERLINES.iter().fold(style, |s, &u| s.without(u));
+                    match sub.next() {
+                        None | Some("1") => style.with(Styles::Underline),
+                        Some("0") => style,
+                        Some("2") => style.with(Styles::DoubleUnderline),
+                        Some("3") => style.with(Styles::CurlyUnderline),
+                        Some("4") => style.with(Styles::DottedUnderline),
+                        Some("5") => style.with(Styles::DashedUnderline),
+                        Some(_) => {
+                            return Err(SgrError::new(
+                                params,
+                                format!("unknown underline style {:?}", code),
+                            ))
+                        }
+                    }
+                }
+                5 => style.with(Styles::Blink),
+                6 => style.with(Styles::RapidBlink),
+                7 => style.with(Styles::Reversed),
+                8 => style.with(Styles::Hidden),
+                9 => style.with(Styles::Strikethrough),
+                21 => style.with(Styles::DoubleUnderline),
+                22 => style.without(Styles::Bold).without(Styles::Dimmed),
+                23 => style.without(Styles::Italic),
+                24 => UNDERLINES.iter().fold(style, |s, &u| s.without(u)),
+                25 => style.without(Styles::Blink).without(Styles::RapidBlink),
+                27 => style.without(Styles::Reversed),
+                28 => style.without(Styles::Hidden),
+                29 => style.without(Styles::Strikethrough),
+                30..=37 => style.color(standard_color(value - 30)),
+                38 => style.fg(color_arguments(params, code, sub, &mut codes)?),
+                39 => style.no_fg(),
+                40..=47 => style.on_color(standard_color(value - 40)),
+                48 => style.bg(color_arguments(params, code, sub, &mut codes)?),
+                49 => style.no_bg(),
+                51 => style.with(Styles::Framed),
+                52 => style.with(Styles::Encircled),
+                53 => style.with(Styles::Overline),
+                54 => style.without(Styles::Framed).without(Styles::Encircled),
+                55 => style.without(Styles::Overline),
+                58 => style.underline_color(color_arguments(params, code, sub, &mut codes)?),
+                59 => style.no_underline_color(),
+                90..=97 => style.color(standard_color(value - 90 + 8)),
+                100..=107 => style.on_color(standard_color(value - 100 + 8)),
+                _ => {
+                    return Err(SgrError::new(
+                        params,
+                        format!("unsupported code {:?}", code),
+                    ))
+                }
+            };
+        }
+
+        Ok(style)
+    }
+}
+
+fn standard_color(index: u8) -> Color {
+    Color::from_palette_index(index).unwrap_or(Color::White)
+}
+
+/// Reads the color of `38`, `48` or `58`, from the sub-parameters of `code` when it has some
+/// (`38:5:208`, `38:2::r:g:b` with the empty color space id, or `38:2:r:g:b` without it), from
+/// the next parameters otherwise (`38;5;208`).
+fn color_arguments<'a, I>(
+    params: &str,
+    code: &str,
+    sub: Split<'a, char>,
+    codes: &mut I,
+) -> Result<AllColor, SgrError>
+where
+    I: Iterator<Item = &'a str>,
+{
+    if !code.contains(':') {
+        return extended_color(params, codes);
+    }
+    let mut args: Vec<&str> = sub.collect();
+    if args.len() == 5 && args[0] == "2" {
+        args.remove(1);
+    }
+    let mut args = args.into_iter();
+    let color = extended_color(params, &mut args)?;
+    match args.next() {
+        Some(_) => Err(SgrError::new(
+            params,
+            format!("too many color arguments in {:?}", code),
+        )),
+        None => Ok(color),
+    }
+}
+
+/// Reads the arguments of `38`, `48` and `58`: `5;n` or `2;r;g;b`.
+fn extended_color<'a, I>(params: &str, codes: &mut I) -> Result<AllColor, SgrError>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let mut next = |what: &str| -> Result<u8, SgrError> {
+        match codes.next() {
+            Some(code) => code
+                .parse()
+                .map_err(|_| SgrError::new(params, format!("invalid {} {:?}", what, code))),
+            None => Err(SgrError::new(params, format!("missing {}", what))),
+        }
+    };
+
+    match next("color kind")? {
+        5 => Ok(AllColor::Palette(next("palette index")?)),
+        2 => {
+            let r = next("red channel")?;
+            let g = next("green channel")?;
+            let b = next("blue channel")?;
+            Ok(AllColor::True(TrueColor::new(r, g, b)))
+        }
+        kind => Err(SgrError::new(
+            params,
+            format!("unknown color kind {}", kind),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basic_codes() {
+        assert_eq!(Ok(ColoredStyle::new()), ColoredStyle::from_sgr(""));
+        assert_eq!(Ok(ColoredStyle::new()), ColoredStyle::from_sgr("00"));
+        assert_eq!(
+            Ok(ColoredStyle::new().bold().color(Color::Blue)),
+            ColoredStyle::from_sgr("01;34")
+        );
+        assert_eq!(
+            Ok(ColoredStyle::new()
+                .color(Color::BrightRed)
+                .on_color(Color::BrightBlack)),
+            ColoredStyle::from_sgr("91;100")
+        );
+    }
+
+    #[test]
+    fn extended_colors() {
+        assert_eq!(
+            Ok(ColoredStyle::new().palette(208).on_true_color(1, 2, 3)),
+            ColoredStyle::from_sgr("38;5;208;48;2;1;2;3")
+        );
+        let red_underline =
+            ColoredStyle::new().underline_color(AllColor::True(TrueColor::new(255, 0, 0)));
+        assert_eq!(
+            Ok(red_underline.bold()),
+            ColoredStyle::from_sgr("58;2;255;0;0;1")
+        );
+        assert_eq!(Ok(ColoredStyle::new()), red_underline.apply_sgr("59"));
+    }
+
+    #[test]
+    fn colon_colors() {
+        assert_eq!(
+            Ok(ColoredStyle::new().palette(208).with(Styles::Blink)),
+            ColoredStyle::from_sgr("38:5:208;5")
+        );
+        assert_eq!(
+            Ok(ColoredStyle::new().palette(208).bold()),
+            ColoredStyle::from_sgr("38:5:208;1")
+        );
+        assert_eq!(
+            Ok(ColoredStyle::new().on_true_color(1, 2, 3)),
+            ColoredStyle::from_sgr("48:2::1:2:3")
+        );
+        assert_eq!(
+            Ok(ColoredStyle::new().on_true_color(1, 2, 3)),
+            ColoredStyle::from_sgr("48:2:1:2:3")
+        );
+        assert_eq!(
+            Ok(ColoredStyle::new()
+                .with(Styles::CurlyUnderline)
+                .underline_color(AllColor::True(TrueColor::new(255, 0, 0)))),
+            ColoredStyle::from_sgr("4:3;58:2::255:0:0")
+        );
+        assert!(ColoredStyle::from_sgr("38:5").is_err());
+        assert!(ColoredStyle::from_sgr("38:5:1:2").is_err());
+        assert!(ColoredStyle::from_sgr("38:2:1:2").is_err());
+    }
+
+    #[test]
+    fn underline_styles() {
+        assert_eq!(
+            Ok(ColoredStyle::new().with(Styles::CurlyUnderline)),
+            ColoredStyle::from_sgr("4;4:3")
+        );
+        assert_eq!(Ok(ColoredStyle::new()), ColoredStyle::from_sgr("4:3;4:0"));
+    }
+
+    #[test]
+    fn off_codes() {
+        let style = ColoredStyle::new()
+            .bold()
+            .italic()
+            .color(Color::Red)
+            .on_color(Color::Blue);
+
+        assert_eq!(
+            Ok(ColoredStyle::new().italic()),
+            style.apply_sgr("22;39;49")
+        );
+        assert_eq!(Ok(ColoredStyle::new()), style.apply_sgr("0"));
+    }
+
+    #[test]
+    fn roundtrip_with_compute_style() {
+        use Colorize;
+
+        let colored = ""
+            .curly_underline()
+            .overline()
+            .on_palette(42)
+            .true_color(1, 2, 3);
+        let sequence = colored.compute_style();
+        let params = &sequence[2..sequence.len() - 1];
+
+        assert_eq!(
+            Ok(ColoredStyle::new()
+                .with(Styles::CurlyUnderline)
+                .with(Styles::Overline)
+                .on_palette(42)
+                .true_color(1, 2, 3)),
+            ColoredStyle::from_sgr(params)
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let err = ColoredStyle::from_sgr("01;12").unwrap_err();
+        assert_eq!(
+            "unsupported code \"12\" in SGR parameters \"01;12\"",
+            err.to_string()
+        );
+
+        let err = ColoredStyle::from_sgr("38;5").unwrap_err();
+        assert_eq!(
+            "missing palette index in SGR parameters \"38;5\"",
+            err.to_string()
+        );
+
+        assert!(ColoredStyle::from_sgr("bold").is_err());
+        assert!(ColoredStyle::from_sgr("38;3;1").is_err());
+        assert!(ColoredStyle::from_sgr("38;5;256").is_err());
+    }
+}
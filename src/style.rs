@@ -1,14 +1,29 @@
-const CLEARV: u8 = 0b0000_0000;
-const BOLD: u8 = 0b0000_0001;
-const UNDERLINE: u8 = 0b0000_0010;
-const REVERSED: u8 = 0b0000_0100;
-const ITALIC: u8 = 0b0000_1000;
-const BLINK: u8 = 0b0001_0000;
-const HIDDEN: u8 = 0b0010_0000;
-const DIMMED: u8 = 0b0100_0000;
-const STRIKETHROUGH: u8 = 0b1000_0000;
-
-static STYLES: [(u8, Styles); 8] = [
+use std::fmt;
+use std::ops::{Add, BitOr};
+use std::str::FromStr;
+
+use color::{AllColor, Color, TrueColor};
+use ColoredString;
+
+const CLEARV: u16 = 0b0000_0000_0000_0000;
+const BOLD: u16 = 0b0000_0000_0000_0001;
+const UNDERLINE: u16 = 0b0000_0000_0000_0010;
+const REVERSED: u16 = 0b0000_0000_0000_0100;
+const ITALIC: u16 = 0b0000_0000_0000_1000;
+const BLINK: u16 = 0b0000_0000_0001_0000;
+const HIDDEN: u16 = 0b0000_0000_0010_0000;
+const DIMMED: u16 = 0b0000_0000_0100_0000;
+const STRIKETHROUGH: u16 = 0b0000_0000_1000_0000;
+const DOUBLE_UNDERLINE: u16 = 0b0000_0001_0000_0000;
+const CURLY_UNDERLINE: u16 = 0b0000_0010_0000_0000;
+const DOTTED_UNDERLINE: u16 = 0b0000_0100_0000_0000;
+const DASHED_UNDERLINE: u16 = 0b0000_1000_0000_0000;
+const OVERLINE: u16 = 0b0001_0000_0000_0000;
+const RAPID_BLINK: u16 = 0b0010_0000_0000_0000;
+const FRAMED: u16 = 0b0100_0000_0000_0000;
+const ENCIRCLED: u16 = 0b1000_0000_0000_0000;
+
+static STYLES: [(u16, Styles); 16] = [
     (BOLD, Styles::Bold),
     (DIMMED, Styles::Dimmed),
     (UNDERLINE, Styles::Underline),
@@ -17,13 +32,22 @@ static STYLES: [(u8, Styles); 8] = [
     (BLINK, Styles::Blink),
     (HIDDEN, Styles::Hidden),
     (STRIKETHROUGH, Styles::Strikethrough),
+    (DOUBLE_UNDERLINE, Styles::DoubleUnderline),
+    (CURLY_UNDERLINE, Styles::CurlyUnderline),
+    (DOTTED_UNDERLINE, Styles::DottedUnderline),
+    (DASHED_UNDERLINE, Styles::DashedUnderline),
+    (OVERLINE, Styles::Overline),
+    (RAPID_BLINK, Styles::RapidBlink),
+    (FRAMED, Styles::Framed),
+    (ENCIRCLED, Styles::Encircled),
 ];
 
 pub static CLEAR: Style = Style(CLEARV);
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
-pub struct Style(u8);
+pub struct Style(u16);
 
+/// A single style (or attribute) that can be applied to a `ColoredString`.
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Styles {
     Clear,
@@ -35,6 +59,14 @@ pub enum Styles {
     Blink,
     Hidden,
     Strikethrough,
+    DoubleUnderline,
+    CurlyUnderline,
+    DottedUnderline,
+    DashedUnderline,
+    Overline,
+    RapidBlink,
+    Framed,
+    Encircled,
 }
 
 impl Styles {
@@ -49,10 +81,38 @@ impl Styles {
             Styles::Reversed => "7",
             Styles::Hidden => "8",
             Styles::Strikethrough => "9",
+            Styles::DoubleUnderline => "21",
+            Styles::CurlyUnderline => "4:3",
+            Styles::DottedUnderline => "4:4",
+            Styles::DashedUnderline => "4:5",
+            Styles::Overline => "53",
+            Styles::RapidBlink => "6",
+            Styles::Framed => "51",
+            Styles::Encircled => "52",
         }
     }
 
-    fn to_u8(self) -> u8 {
+    /// The SGR code which turns this style off without resetting everything else.
+    fn to_off_str<'a>(self) -> &'a str {
+        match self {
+            Styles::Clear => "", // unreachable, but we don't want to panic
+            Styles::Bold | Styles::Dimmed => "22",
+            Styles::Italic => "23",
+            Styles::Underline
+            | Styles::DoubleUnderline
+            | Styles::CurlyUnderline
+            | Styles::DottedUnderline
+            | Styles::DashedUnderline => "24",
+            Styles::Blink | Styles::RapidBlink => "25",
+            Styles::Reversed => "27",
+            Styles::Hidden => "28",
+            Styles::Strikethrough => "29",
+            Styles::Framed | Styles::Encircled => "54",
+            Styles::Overline => "55",
+        }
+    }
+
+    const fn to_u16(self) -> u16 {
         match self {
             Styles::Clear => CLEARV,
             Styles::Bold => BOLD,
@@ -63,17 +123,25 @@ impl Styles {
             Styles::Reversed => REVERSED,
             Styles::Hidden => HIDDEN,
             Styles::Strikethrough => STRIKETHROUGH,
+            Styles::DoubleUnderline => DOUBLE_UNDERLINE,
+            Styles::CurlyUnderline => CURLY_UNDERLINE,
+            Styles::DottedUnderline => DOTTED_UNDERLINE,
+            Styles::DashedUnderline => DASHED_UNDERLINE,
+            Styles::Overline => OVERLINE,
+            Styles::RapidBlink => RAPID_BLINK,
+            Styles::Framed => FRAMED,
+            Styles::Encircled => ENCIRCLED,
         }
     }
 
-    fn from_u8(u: u8) -> Option<Vec<Styles>> {
+    fn from_u16(u: u16) -> Option<Vec<Styles>> {
         if u == CLEARV {
             return None;
         }
 
         let res: Vec<Styles> = STYLES
-            .into_iter()
-            .filter(|&&(ref mask, _)| (0 != (u & mask)))
+            .iter()
+            .filter(|&&(mask, _)| (0 != (u & mask)))
             .map(|&(_, value)| value)
             .collect();
         if res.is_empty() {
@@ -84,9 +152,64 @@ impl Styles {
     }
 }
 
+impl fmt::Display for Styles {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            Styles::Clear => "clear",
+            Styles::Bold => "bold",
+            Styles::Dimmed => "dimmed",
+            Styles::Underline => "underline",
+            Styles::Reversed => "reversed",
+            Styles::Italic => "italic",
+            Styles::Blink => "blink",
+            Styles::Hidden => "hidden",
+            Styles::Strikethrough => "strikethrough",
+            Styles::DoubleUnderline => "double_underline",
+            Styles::CurlyUnderline => "curly_underline",
+            Styles::DottedUnderline => "dotted_underline",
+            Styles::DashedUnderline => "dashed_underline",
+            Styles::Overline => "overline",
+            Styles::RapidBlink => "rapid_blink",
+            Styles::Framed => "framed",
+            Styles::Encircled => "encircled",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Parses the names of the `Colorize` methods: `"bold"`, `"curly_underline"`...
+impl FromStr for Styles {
+    type Err = ();
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let src = src.to_lowercase();
+
+        match src.as_ref() {
+            "clear" | "normal" => Ok(Styles::Clear),
+            "bold" => Ok(Styles::Bold),
+            "dimmed" => Ok(Styles::Dimmed),
+            "underline" => Ok(Styles::Underline),
+            "reversed" | "reverse" => Ok(Styles::Reversed),
+            "italic" => Ok(Styles::Italic),
+            "blink" => Ok(Styles::Blink),
+            "hidden" => Ok(Styles::Hidden),
+            "strikethrough" => Ok(Styles::Strikethrough),
+            "double_underline" => Ok(Styles::DoubleUnderline),
+            "curly_underline" => Ok(Styles::CurlyUnderline),
+            "dotted_underline" => Ok(Styles::DottedUnderline),
+            "dashed_underline" => Ok(Styles::DashedUnderline),
+            "overline" => Ok(Styles::Overline),
+            "rapid_blink" => Ok(Styles::RapidBlink),
+            "framed" => Ok(Styles::Framed),
+            "encircled" => Ok(Styles::Encircled),
+            _ => Err(()),
+        }
+    }
+}
+
 impl Style {
     pub fn to_str(self) -> String {
-        let styles = match Styles::from_u8(self.0) {
+        let styles = match Styles::from_u16(self.0) {
             None => return String::new(),
             Some(s) => s,
         };
@@ -106,55 +229,416 @@ impl Style {
         res
     }
 
-    pub fn new(from: Styles) -> Style {
-        Style(from.to_u8())
+    /// The sequence turning off every style of `self`. Styles sharing the same code (like bold
+    /// and dimmed) only write it once.
+    pub fn to_off_str(self) -> String {
+        let styles = match Styles::from_u16(self.0) {
+            None => return String::new(),
+            Some(s) => s,
+        };
+        let mut codes: Vec<&str> = Vec::with_capacity(styles.len());
+
+        for code in styles.iter().map(|s| s.to_off_str()) {
+            if !codes.contains(&code) {
+                codes.push(code);
+            }
+        }
+        codes.join(";")
+    }
+
+    pub const fn new(from: Styles) -> Style {
+        Style(from.to_u16())
+    }
+
+    pub const fn from_both(one: Style, two: Styles) -> Style {
+        Style(one.0 | two.to_u16())
+    }
+
+    pub const fn union(one: Style, two: Style) -> Style {
+        Style(one.0 | two.0)
+    }
+
+    pub const fn without(one: Style, two: Styles) -> Style {
+        Style(one.0 & !two.to_u16())
+    }
+
+    /// The styles of `one` which are not in `two`.
+    pub fn difference(one: Style, two: Style) -> Style {
+        Style(one.0 & !two.0)
+    }
+}
+
+/// A reusable combination of colors and styles, to apply to any string.
+///
+/// It is `Copy` and can be built in `const` and `static` contexts. Combining two styles with
+/// `|` (or `+`) merges their styles, the colors of the right-hand side taking precedence.
+///
+/// # Example
+/// ```
+/// # use colored::*;
+/// const ERROR: ColoredStyle = ColoredStyle::new().color(Color::BrightRed).on_color(Color::Black).bold();
+///
+/// let loud = ERROR | Styles::Underline;
+/// assert_eq!(loud.apply("oops"), "oops".bright_red().on_black().bold().underline());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ColoredStyle {
+    fgcolor: Option<AllColor>,
+    bgcolor: Option<AllColor>,
+    underline_color: Option<AllColor>,
+    style: Style,
+}
+
+impl ColoredStyle {
+    /// A style without any color or attribute.
+    pub const fn new() -> ColoredStyle {
+        ColoredStyle {
+            fgcolor: None,
+            bgcolor: None,
+            underline_color: None,
+            style: Style(CLEARV),
+        }
+    }
+
+    pub const fn fg(self, color: AllColor) -> ColoredStyle {
+        ColoredStyle {
+            fgcolor: Some(color),
+            ..self
+        }
+    }
+
+    pub const fn bg(self, color: AllColor) -> ColoredStyle {
+        ColoredStyle {
+            bgcolor: Some(color),
+            ..self
+        }
+    }
+
+    /// The color of the underline, for the terminals that draw it (SGR 58).
+    pub const fn underline_color(self, color: AllColor) -> ColoredStyle {
+        ColoredStyle {
+            underline_color: Some(color),
+            ..self
+        }
+    }
+
+    pub const fn color(self, color: Color) -> ColoredStyle {
+        self.fg(AllColor::Color(color))
+    }
+
+    pub const fn on_color(self, color: Color) -> ColoredStyle {
+        self.bg(AllColor::Color(color))
+    }
+
+    pub const fn true_color(self, r: u8, g: u8, b: u8) -> ColoredStyle {
+        self.fg(AllColor::True(TrueColor::new(r, g, b)))
+    }
+
+    pub const fn on_true_color(self, r: u8, g: u8, b: u8) -> ColoredStyle {
+        self.bg(AllColor::True(TrueColor::new(r, g, b)))
+    }
+
+    pub const fn palette(self, code: u8) -> ColoredStyle {
+        self.fg(AllColor::Palette(code))
+    }
+
+    pub const fn on_palette(self, code: u8) -> ColoredStyle {
+        self.bg(AllColor::Palette(code))
+    }
+
+    /// Adds a style to this one.
+    pub const fn with(self, style: Styles) -> ColoredStyle {
+        ColoredStyle {
+            style: Style::from_both(self.style, style),
+            ..self
+        }
+    }
+
+    /// Removes a style from this one.
+    pub const fn without(self, style: Styles) -> ColoredStyle {
+        ColoredStyle {
+            style: Style::without(self.style, style),
+            ..self
+        }
+    }
+
+    pub const fn no_fg(self) -> ColoredStyle {
+        ColoredStyle {
+            fgcolor: None,
+            ..self
+        }
+    }
+
+    pub const fn no_bg(self) -> ColoredStyle {
+        ColoredStyle {
+            bgcolor: None,
+            ..self
+        }
+    }
+
+    pub const fn no_underline_color(self) -> ColoredStyle {
+        ColoredStyle {
+            underline_color: None,
+            ..self
+        }
+    }
+
+    pub const fn bold(self) -> ColoredStyle {
+        self.with(Styles::Bold)
+    }
+
+    pub const fn dimmed(self) -> ColoredStyle {
+        self.with(Styles::Dimmed)
+    }
+
+    pub const fn italic(self) -> ColoredStyle {
+        self.with(Styles::Italic)
+    }
+
+    pub const fn underline(self) -> ColoredStyle {
+        self.with(Styles::Underline)
+    }
+
+    pub const fn reversed(self) -> ColoredStyle {
+        self.with(Styles::Reversed)
+    }
+
+    pub const fn strikethrough(self) -> ColoredStyle {
+        self.with(Styles::Strikethrough)
+    }
+
+    pub fn fgcolor(&self) -> Option<AllColor> {
+        self.fgcolor
+    }
+
+    pub fn bgcolor(&self) -> Option<AllColor> {
+        self.bgcolor
+    }
+
+    pub fn ulcolor(&self) -> Option<AllColor> {
+        self.underline_color
+    }
+
+    /// The styles (bold, italic...) of this style.
+    pub fn styles(&self) -> Vec<Styles> {
+        Styles::from_u16(self.style.0).unwrap_or_default()
+    }
+
+    /// Whether this style contains the given style.
+    pub fn has(&self, style: Styles) -> bool {
+        Style::from_both(self.style, style) == self.style
+    }
+
+    /// Applies this style to `input`.
+    pub fn apply(self, input: &str) -> ColoredString {
+        self.paint(ColoredString::from(input))
+    }
+
+    /// Applies this style on top of the colors and styles `colored` already has.
+    pub(crate) fn paint(self, colored: ColoredString) -> ColoredString {
+        ColoredString {
+            fgcolor: self.fgcolor.or(colored.fgcolor),
+            bgcolor: self.bgcolor.or(colored.bgcolor),
+            underline_color: self.underline_color.or(colored.underline_color),
+            style: Style::union(colored.style, self.style),
+            ..colored
+        }
+    }
+}
+
+impl Default for ColoredStyle {
+    fn default() -> Self {
+        ColoredStyle::new()
+    }
+}
+
+impl From<Styles> for ColoredStyle {
+    fn from(style: Styles) -> Self {
+        ColoredStyle::new().with(style)
+    }
+}
+
+impl BitOr for ColoredStyle {
+    type Output = ColoredStyle;
+
+    fn bitor(self, other: ColoredStyle) -> ColoredStyle {
+        ColoredStyle {
+            fgcolor: other.fgcolor.or(self.fgcolor),
+            bgcolor: other.bgcolor.or(self.bgcolor),
+            underline_color: other.underline_color.or(self.underline_color),
+            style: Style::union(self.style, other.style),
+        }
+    }
+}
+
+impl BitOr<Styles> for ColoredStyle {
+    type Output = ColoredStyle;
+
+    fn bitor(self, other: Styles) -> ColoredStyle {
+        self.with(other)
+    }
+}
+
+impl Add for ColoredStyle {
+    type Output = ColoredStyle;
+
+    fn add(self, other: ColoredStyle) -> ColoredStyle {
+        self.bitor(other)
     }
+}
 
-    pub fn from_both(one: Style, two: Styles) -> Style {
-        Style(one.0 | two.to_u8())
+impl Add<Styles> for ColoredStyle {
+    type Output = ColoredStyle;
+
+    fn add(self, other: Styles) -> ColoredStyle {
+        self.bitor(other)
     }
 }
 
 #[cfg(test)]
 mod tests {
 
-    mod u8_to_styles_invalid_is_none {
+    mod colored_style {
+        use super::super::*;
+        use Colorize;
+
+        static WARNING: ColoredStyle = ColoredStyle::new().color(Color::Yellow).bold();
+
+        #[test]
+        fn empty_is_plain() {
+            assert!(ColoredStyle::new().apply("text").is_plain());
+            assert_eq!(ColoredStyle::new(), ColoredStyle::default());
+        }
+
+        #[test]
+        fn apply() {
+            assert_eq!(WARNING.apply("careful"), "careful".yellow().bold());
+        }
+
+        #[test]
+        fn compose_colors_right_wins() {
+            let red = ColoredStyle::new().color(Color::Red).on_color(Color::Black);
+            let blue = ColoredStyle::new().color(Color::Blue);
+
+            let composed = red | blue;
+            assert_eq!(Some(AllColor::Color(Color::Blue)), composed.fgcolor());
+            assert_eq!(Some(AllColor::Color(Color::Black)), composed.bgcolor());
+        }
+
+        #[test]
+        fn compose_styles() {
+            let composed = WARNING + ColoredStyle::from(Styles::Italic) + Styles::Underline;
+
+            assert!(composed.has(Styles::Bold));
+            assert!(composed.has(Styles::Italic));
+            assert!(composed.has(Styles::Underline));
+            assert!(!composed.has(Styles::Blink));
+            assert_eq!(composed, WARNING | Styles::Italic | Styles::Underline);
+        }
+
+        #[test]
+        fn styles() {
+            assert_eq!(Vec::<Styles>::new(), ColoredStyle::new().styles());
+            assert_eq!(vec![Styles::Bold], WARNING.styles());
+        }
+
+        #[test]
+        fn true_colors_and_palette() {
+            let style = ColoredStyle::new().true_color(1, 2, 3).on_palette(42);
+
+            assert_eq!(style.apply("x"), "x".true_color(1, 2, 3).on_palette(42));
+        }
+    }
+
+    mod u16_to_styles_invalid_is_none {
         use super::super::CLEARV;
         use super::super::{Style, Styles};
 
         #[test]
         fn empty_is_none() {
-            assert_eq!(None, Styles::from_u8(CLEARV))
+            assert_eq!(None, Styles::from_u16(CLEARV))
+        }
+    }
+
+    mod styles_from_str {
+        use super::super::{Styles, STYLES};
+
+        #[test]
+        fn roundtrip() {
+            for &(_, style) in STYLES.iter() {
+                assert_eq!(Ok(style), style.to_string().parse());
+            }
+        }
+
+        #[test]
+        fn aliases() {
+            assert_eq!(Ok(Styles::Reversed), "reverse".parse());
+            assert_eq!(Ok(Styles::Bold), "BOLD".parse());
+            assert_eq!(Err(()), "blod".parse::<Styles>());
+        }
+    }
+
+    mod style_off {
+        use super::super::Styles::*;
+        use super::super::{Style, CLEAR};
+
+        #[test]
+        fn clear_is_empty() {
+            assert_eq!("", CLEAR.to_off_str())
+        }
+
+        #[test]
+        fn without() {
+            let style = Style::from_both(Style::new(Bold), Italic);
+            assert_eq!(Style::new(Italic), Style::without(style, Bold));
+            assert_eq!(style, Style::without(style, Underline));
+        }
+
+        #[test]
+        fn shared_codes_are_written_once() {
+            let style = Style::from_both(Style::new(Bold), Dimmed);
+            let style = Style::from_both(style, Underline);
+            let style = Style::from_both(style, CurlyUnderline);
+            assert_eq!("22;24", style.to_off_str())
+        }
+
+        #[test]
+        fn difference() {
+            let one = Style::from_both(Style::new(Bold), Italic);
+            let two = Style::from_both(Style::new(Bold), Underline);
+            assert_eq!(Style::new(Italic), Style::difference(one, two));
         }
 
     }
 
-    mod u8_to_styles_isomorphism {
+    mod u16_to_styles_isomorphism {
         use super::super::Styles;
         use super::super::{
-            BLINK, BOLD, DIMMED, HIDDEN, ITALIC, REVERSED, STRIKETHROUGH, UNDERLINE,
+            BLINK, BOLD, CURLY_UNDERLINE, DASHED_UNDERLINE, DIMMED, DOTTED_UNDERLINE,
+            DOUBLE_UNDERLINE, ENCIRCLED, FRAMED, HIDDEN, ITALIC, OVERLINE, RAPID_BLINK, REVERSED,
+            STRIKETHROUGH, UNDERLINE,
         };
 
         macro_rules! value_isomorph {
             ($name:ident, $value:expr) => {
                 #[test]
                 fn $name() {
-                    let u = Styles::from_u8($value);
+                    let u = Styles::from_u16($value);
                     assert!(
                         u.is_some(),
-                        "{}: Styles::from_u8 -> None",
+                        "{}: Styles::from_u16 -> None",
                         stringify!($value)
                     );
                     let u = u.unwrap();
                     assert!(
                         u.len() == 1,
-                        "{}: Styles::from_u8 found {} styles (expected 1)",
+                        "{}: Styles::from_u16 found {} styles (expected 1)",
                         stringify!($value),
                         u.len()
                     );
                     assert!(
-                        u[0].to_u8() == $value,
-                        "{}: to_u8() doesn't match its const value",
+                        u[0].to_u16() == $value,
+                        "{}: to_u16() doesn't match its const value",
                         stringify!($value)
                     );
                 }
@@ -169,13 +653,23 @@ mod tests {
         value_isomorph!(hidden, HIDDEN);
         value_isomorph!(dimmed, DIMMED);
         value_isomorph!(strikethrough, STRIKETHROUGH);
+        value_isomorph!(double_underline, DOUBLE_UNDERLINE);
+        value_isomorph!(curly_underline, CURLY_UNDERLINE);
+        value_isomorph!(dotted_underline, DOTTED_UNDERLINE);
+        value_isomorph!(dashed_underline, DASHED_UNDERLINE);
+        value_isomorph!(overline, OVERLINE);
+        value_isomorph!(rapid_blink, RAPID_BLINK);
+        value_isomorph!(framed, FRAMED);
+        value_isomorph!(encircled, ENCIRCLED);
     }
 
     mod styles_combine_complex {
         use super::super::Styles::*;
         use super::super::{Style, Styles};
         use super::super::{
-            BLINK, BOLD, DIMMED, HIDDEN, ITALIC, REVERSED, STRIKETHROUGH, UNDERLINE,
+            BLINK, BOLD, CURLY_UNDERLINE, DASHED_UNDERLINE, DIMMED, DOTTED_UNDERLINE,
+            DOUBLE_UNDERLINE, ENCIRCLED, FRAMED, HIDDEN, ITALIC, OVERLINE, RAPID_BLINK, REVERSED,
+            STRIKETHROUGH, UNDERLINE,
         };
 
         fn style_from_multiples(styles: &[Styles]) -> Style {
@@ -189,7 +683,7 @@ mod tests {
         macro_rules! test_aggreg {
             ($styles:expr, $expect:expr) => {{
                 let v = style_from_multiples($styles);
-                let r = Styles::from_u8(v.0).expect("should find styles");
+                let r = Styles::from_u16(v.0).expect("should find styles");
                 assert_eq!(&$expect as &[Styles], &r[..])
             }};
         }
@@ -215,7 +709,7 @@ mod tests {
         macro_rules! test_combine {
             ($styles:expr) => {{
                 let v = style_from_multiples($styles);
-                let r = Styles::from_u8(v.0).expect("should find styles");
+                let r = Styles::from_u16(v.0).expect("should find styles");
                 assert_eq!($styles, &r[..])
             }};
         }
@@ -283,5 +777,34 @@ mod tests {
             test_combine!(s)
         }
 
+        #[test]
+        fn extended() {
+            let s: &[Styles] = &[Bold, DoubleUnderline, Overline, Framed];
+            test_combine!(s)
+        }
+
+        #[test]
+        fn all_extended() {
+            let s: &[Styles] = &[
+                Bold,
+                Dimmed,
+                Underline,
+                Reversed,
+                Italic,
+                Blink,
+                Hidden,
+                Strikethrough,
+                DoubleUnderline,
+                CurlyUnderline,
+                DottedUnderline,
+                DashedUnderline,
+                Overline,
+                RapidBlink,
+                Framed,
+                Encircled,
+            ];
+            test_combine!(s)
+        }
+
     }
 }
@@ -0,0 +1,463 @@
+//! Tables of colored cells, their columns sized by the visible width of the cells.
+//!
+//! ```
+//! use colored::*;
+//! use colored::table::{Align, Border, Table};
+//!
+//! let mut table = Table::new()
+//!     .header(vec!["crate", "downloads"])
+//!     .align(1, Align::Right)
+//!     .border(Border::Rounded)
+//!     .header_style(ColoredStyle::new().bold())
+//!     .stripes(ColoredStyle::new().on_palette(236));
+//! table.add_row(vec!["colored".green(), "42M".normal()]);
+//! table.add_row(vec!["lazy_static".yellow(), "250M".bold()]);
+//! println!("{}", table);
+//! ```
+
+use std::fmt;
+use std::iter;
+
+use style::ColoredStyle;
+use width::{truncate, visible_width};
+use wrap::{split_lines, wrap, WrapOptions};
+
+/// The alignment of the cells of a column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+/// The lines drawn around and between the cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Border {
+    /// No lines, the columns being separated by two spaces.
+    None,
+    /// `+`, `-` and `|`.
+    Ascii,
+    /// `┌─┬─┐`
+    Light,
+    /// `┏━┳━┓`
+    Heavy,
+    /// `╭─┬─╮`
+    Rounded,
+}
+
+/// What happens to the cells wider than the maximum width of their column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    /// Cut, with an ellipsis.
+    Truncate,
+    /// Wrapped on several lines.
+    Wrap,
+}
+
+/// The characters of a border: the horizontal and vertical lines, then the top, middle and
+/// bottom junctions, from left to right.
+struct BorderChars {
+    horizontal: char,
+    vertical: char,
+    top: [char; 3],
+    middle: [char; 3],
+    bottom: [char; 3],
+}
+
+impl Border {
+    fn chars(self) -> Option<BorderChars> {
+        let (horizontal, vertical, top, middle, bottom) = match self {
+            Border::None => return None,
+            Border::Ascii => ('-', '|', ['+'; 3], ['+'; 3], ['+'; 3]),
+            Border::Light => ('─', '│', ['┌', '┬', '┐'], ['├', '┼', '┤'], ['└', '┴', '┘']),
+            Border::Heavy => ('━', '┃', ['┏', '┳', '┓'], ['┣', '╋', '┫'], ['┗', '┻', '┛']),
+            Border::Rounded => ('─', '│', ['╭', '┬', '╮'], ['├', '┼', '┤'], ['╰', '┴', '╯']),
+        };
+        Some(BorderChars {
+            horizontal,
+            vertical,
+            top,
+            middle,
+            bottom,
+        })
+    }
+}
+
+/// A table, rendered by its `Display` implementation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Table {
+    header: Option<Vec<String>>,
+    rows: Vec<Vec<String>>,
+    aligns: Vec<Align>,
+    max_widths: Vec<Option<usize>>,
+    border: Border,
+    overflow: Overflow,
+    border_style: ColoredStyle,
+    header_style: ColoredStyle,
+    stripe_style: Option<ColoredStyle>,
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Table::new()
+    }
+}
+
+fn cells<I, T>(cells: I) -> Vec<String>
+where
+    I: IntoIterator<Item = T>,
+    T: fmt::Display,
+{
+    cells.into_iter().map(|cell| cell.to_string()).collect()
+}
+
+impl Table {
+    /// An empty table, with light borders and left aligned columns.
+    pub fn new() -> Table {
+        Table {
+            header: None,
+            rows: Vec::new(),
+            aligns: Vec::new(),
+            max_widths: Vec::new(),
+            border: Border::Light,
+            overflow: Overflow::Truncate,
+            border_style: ColoredStyle::new(),
+            header_style: ColoredStyle::new(),
+            stripe_style: None,
+        }
+    }
+
+    /// The header cells: `ColoredString`s, `&str`s, or anything else displayable.
+    pub fn header<I, T>(self, header: I) -> Table
+    where
+        I: IntoIterator<Item = T>,
+        T: fmt::Display,
+    {
+        Table {
+            header: Some(cells(header)),
+            ..self
+        }
+    }
+
+    pub fn add_row<I, T>(&mut self, row: I)
+    where
+        I: IntoIterator<Item = T>,
+        T: fmt::Display,
+    {
+        self.rows.push(cells(row));
+    }
+
+    pub fn with_row<I, T>(mut self, row: I) -> Table
+    where
+        I: IntoIterator<Item = T>,
+        T: fmt::Display,
+    {
+        self.add_row(row);
+        self
+    }
+
+    pub fn align(mut self, column: usize, align: Align) -> Table {
+        if self.aligns.len() <= column {
+            self.aligns.resize(column + 1, Align::Left);
+        }
+        self.aligns[column] = align;
+        self
+    }
+
+    /// Limits the width of a column, the wider cells being truncated or wrapped.
+    pub fn max_width(mut self, column: usize, width: usize) -> Table {
+        if self.max_widths.len() <= column {
+            self.max_widths.resize(column + 1, None);
+        }
+        self.max_widths[column] = Some(width.max(1));
+        self
+    }
+
+    pub fn border(self, border: Border) -> Table {
+        Table { border, ..self }
+    }
+
+    pub fn overflow(self, overflow: Overflow) -> Table {
+        Table { overflow, ..self }
+    }
+
+    pub fn border_style(self, border_style: ColoredStyle) -> Table {
+        Table {
+            border_style,
+            ..self
+        }
+    }
+
+    /// The style of the header row, cells and padding included.
+    pub fn header_style(self, header_style: ColoredStyle) -> Table {
+        Table {
+            header_style,
+            ..self
+        }
+    }
+
+    /// The style of every other row, typically a background color (`on_*`).
+    pub fn stripes(self, stripe_style: ColoredStyle) -> Table {
+        Table {
+            stripe_style: Some(stripe_style),
+            ..self
+        }
+    }
+
+    fn columns(&self) -> usize {
+        self.header
+            .iter()
+            .chain(&self.rows)
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn widths(&self) -> Vec<usize> {
+        (0..self.columns())
+            .map(|column| {
+                let width = self
+                    .header
+                    .iter()
+                    .chain(&self.rows)
+                    .filter_map(|row| row.get(column))
+                    .flat_map(|cell| cell.split('\n'))
+                    .map(visible_width)
+                    .max()
+                    .unwrap_or(0);
+                match self.max_widths.get(column) {
+                    Some(&Some(max)) => width.min(max),
+                    _ => width,
+                }
+            })
+            .collect()
+    }
+
+    /// The lines of a cell, fitting in `width`.
+    fn cell_lines(&self, cell: &str, width: usize) -> Vec<String> {
+        match self.overflow {
+            Overflow::Truncate => split_lines(cell)
+                .iter()
+                .map(|line| truncate(line, width, "…"))
+                .collect(),
+            Overflow::Wrap => wrap(cell, &WrapOptions::new(width)),
+        }
+    }
+
+    fn pad(&self, line: &str, column: usize, width: usize) -> String {
+        let extra = width.saturating_sub(visible_width(line));
+        let left = match self.aligns.get(column).cloned().unwrap_or(Align::Left) {
+            Align::Left => 0,
+            Align::Right => extra,
+            Align::Center => extra / 2,
+        };
+        format!("{}{}{}", " ".repeat(left), line, " ".repeat(extra - left))
+    }
+
+    /// A horizontal line of the borders.
+    fn rule(&self, widths: &[usize], junctions: [char; 3], horizontal: char) -> String {
+        let mut rule = String::new();
+        rule.push(junctions[0]);
+        for (idx, &width) in widths.iter().enumerate() {
+            if idx > 0 {
+                rule.push(junctions[1]);
+            }
+            rule.extend(iter::repeat(horizontal).take(width + 2));
+        }
+        rule.push(junctions[2]);
+        self.border_style.apply(&rule).to_string()
+    }
+
+    /// The lines of a row, as high as its highest cell.
+    fn row(&self, row: &[String], widths: &[usize], style: ColoredStyle) -> Vec<String> {
+        let empty = String::new();
+        let cells: Vec<Vec<String>> = widths
+            .iter()
+            .enumerate()
+            .map(|(column, &width)| self.cell_lines(row.get(column).unwrap_or(&empty), width))
+            .collect();
+        let height = cells.iter().map(Vec::len).max().unwrap_or(1);
+        let vertical = self.border.chars().map(|chars| {
+            self.border_style
+                .apply(&chars.vertical.to_string())
+                .to_string()
+        });
+
+        (0..height)
+            .map(|line| {
+                let mut res = String::new();
+                for (column, &width) in widths.iter().enumerate() {
+                    let content = cells[column].get(line).unwrap_or(&empty);
+                    let padded = self.pad(content, column, width);
+                    let cell = match vertical {
+                        Some(ref vertical) => {
+                            res.push_str(vertical);
+                            format!(" {} ", padded)
+                        }
+                        None if column > 0 => format!("  {}", padded),
+                        None => padded,
+                    };
+                    res.push_str(&style.apply(&cell).to_string());
+                }
+                if let Some(ref vertical) = vertical {
+                    res.push_str(vertical);
+                }
+                res
+            })
+            .collect()
+    }
+
+    /// The lines of the table.
+    pub fn lines(&self) -> Vec<String> {
+        let widths = self.widths();
+        let chars = self.border.chars();
+        let mut lines = Vec::new();
+
+        if let Some(ref chars) = chars {
+            lines.push(self.rule(&widths, chars.top, chars.horizontal));
+        }
+        if let Some(ref header) = self.header {
+            lines.extend(self.row(header, &widths, self.header_style));
+            if let Some(ref chars) = chars {
+                lines.push(self.rule(&widths, chars.middle, chars.horizontal));
+            }
+        }
+        for (idx, row) in self.rows.iter().enumerate() {
+            let style = match self.stripe_style {
+                Some(stripe_style) if idx % 2 == 1 => stripe_style,
+                _ => ColoredStyle::new(),
+            };
+            lines.extend(self.row(row, &widths, style));
+        }
+        if let Some(ref chars) = chars {
+            lines.push(self.rule(&widths, chars.bottom, chars.horizontal));
+        }
+        lines
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.lines().join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use color::Color;
+    use testing::styled_eq;
+    use width::strip_escapes;
+    use Colorize;
+
+    fn table() -> Table {
+        Table::new()
+            .header(vec!["name", "size"])
+            .with_row(vec!["a".red(), "12".normal()])
+            .with_row(vec!["colored".bold(), "3".normal()])
+    }
+
+    #[test]
+    fn borders() {
+        assert_eq!(
+            "┌─────────┬──────┐\n\
+             │ name    │ size │\n\
+             ├─────────┼──────┤\n\
+             │ a       │ 12   │\n\
+             │ colored │ 3    │\n\
+             └─────────┴──────┘",
+            strip_escapes(&table().to_string())
+        );
+        assert_eq!(
+            "+------+---+\n\
+             | name | s |\n\
+             +------+---+\n\
+             +------+---+",
+            Table::new()
+                .header(vec!["name", "s"])
+                .border(Border::Ascii)
+                .to_string()
+        );
+        assert_eq!(
+            "name     size\n\
+             a        12  \n\
+             colored  3   ",
+            strip_escapes(&table().border(Border::None).to_string())
+        );
+    }
+
+    #[test]
+    fn alignment() {
+        let table = table().align(1, Align::Right).border(Border::Heavy);
+        let lines = table.lines();
+
+        assert_eq!("┃ a       ┃   12 ┃", strip_escapes(&lines[3]));
+        assert_eq!("┗━━━━━━━━━┻━━━━━━┛", lines[5]);
+
+        let table = Table::new().with_row(vec!["x"]).with_row(vec!["abc"]);
+        let table = table.align(0, Align::Center).border(Border::Rounded);
+        assert_eq!("╭─────╮\n│  x  │\n│ abc │\n╰─────╯", table.to_string());
+    }
+
+    #[test]
+    fn widths_ignore_escapes() {
+        let table = Table::new()
+            .with_row(vec![format!("{} {}", "日本".bold(), "x".red())])
+            .border(Border::None);
+        assert_eq!(vec![6], table.widths());
+    }
+
+    #[test]
+    fn overflow() {
+        let table = Table::new()
+            .with_row(vec!["hello world", "x"])
+            .max_width(0, 7)
+            .border(Border::Ascii);
+        assert_eq!(
+            "+---------+---+\n| hello … | x |\n+---------+---+",
+            table.to_string()
+        );
+        assert_eq!(
+            "+---------+---+\n| hello   | x |\n| world   |   |\n+---------+---+",
+            table.overflow(Overflow::Wrap).to_string()
+        );
+    }
+
+    #[test]
+    fn multiline_styled_cells() {
+        let table = Table::new()
+            .with_row(vec!["a\nb".red(), "x".normal()])
+            .border(Border::Ascii);
+        // the color is closed before the padding and the border, and opened again on each line
+        assert_eq!(
+            "+---+---+\n\
+             | \x1B[31ma\x1B[0m | x |\n\
+             | \x1B[31mb\x1B[0m |   |\n\
+             +---+---+",
+            table.to_string()
+        );
+        let wrapped = table.clone().overflow(Overflow::Wrap);
+        assert!(styled_eq(&table.to_string(), &wrapped.to_string()));
+    }
+
+    #[test]
+    fn header_and_stripes() {
+        let table = table()
+            .header_style(ColoredStyle::new().bold())
+            .stripes(ColoredStyle::new().on_color(Color::Blue))
+            .border(Border::None);
+        let lines = table.lines();
+
+        assert_eq!(format!("{}{}", "name   ".bold(), "  size".bold()), lines[0]);
+        assert!(!lines[1].contains("\x1B[44m"));
+        // the padding and the nested bold cell are on the stripe
+        assert_eq!(
+            format!(
+                "{}{}",
+                format!("{}", "colored".bold()).on_blue(),
+                "  3   ".on_blue()
+            ),
+            lines[2]
+        );
+    }
+}
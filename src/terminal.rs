@@ -0,0 +1,545 @@
+//! Questions to the terminal about its colors, to adapt the output to them: its background
+//! (OSC 11) and its palette (OSC 4).
+//!
+//! The terminal is asked with escape sequences written to it, and answers with others which
+//! are read back. The questions are followed by a request of the device attributes, which all
+//! terminals answer, so that the ones not knowing the question are not waited for until the
+//! timeout.
+//!
+//! Nothing is asked when the process runs in the background (`cmd &`), where touching the
+//! terminal would stop it: the functions fall back to `COLORFGBG` or to the xterm colors. The
+//! keys typed while waiting for the answer are read with it, and lost.
+//!
+//! ```no_run
+//! use colored::*;
+//! use colored::terminal::{self, Brightness};
+//! use std::time::Duration;
+//!
+//! let brightness = terminal::background_brightness(Duration::from_millis(100));
+//! let warning = match brightness {
+//!     Some(Brightness::Light) => "careful".yellow().on_black(),
+//!     _ => "careful".bright_yellow(),
+//! };
+//! println!("{}", warning);
+//! ```
+
+use std::env;
+use std::io;
+#[cfg(unix)]
+use std::os::unix::io::RawFd;
+use std::time::Duration;
+#[cfg(unix)]
+use std::time::Instant;
+
+use color::TrueColor;
+use palette::{Palette, XTERM_COLORS};
+
+/// Whether a background is light or dark.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Brightness {
+    Light,
+    Dark,
+}
+
+impl Brightness {
+    /// The brightness of a color: light when black text on it is more readable than white text.
+    pub fn of(color: TrueColor) -> Brightness {
+        // the luminance where the contrast with black and with white are the same
+        if color.relative_luminance() > 0.179 {
+            Brightness::Light
+        } else {
+            Brightness::Dark
+        }
+    }
+
+    /// Picks the value for this brightness, like the color of a theme.
+    pub fn pick<T>(self, on_light: T, on_dark: T) -> T {
+        match self {
+            Brightness::Light => on_light,
+            Brightness::Dark => on_dark,
+        }
+    }
+}
+
+/// Parses the colors of the X11 syntax used in the answers of the terminals,
+/// `"rgb:rrrr/gggg/bbbb"`, each channel having 1 to 4 hexadecimal digits.
+pub(crate) fn parse_rgb_spec(spec: &str) -> Option<TrueColor> {
+    let channels: Vec<u8> = spec
+        .strip_prefix("rgb:")?
+        .split('/')
+        .map(|hex| {
+            if hex.is_empty() || hex.len() > 4 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return None;
+            }
+            let value = u32::from_str_radix(hex, 16).ok()?;
+            let max = (1 << (4 * hex.len())) - 1;
+            Some(((value * 255 + max / 2) / max) as u8)
+        })
+        .collect::<Option<_>>()?;
+
+    match channels[..] {
+        [r, g, b] => Some(TrueColor::new(r, g, b)),
+        _ => None,
+    }
+}
+
+/// The bodies of the OSC sequences of an answer, like `"11;rgb:0000/0000/0000"`.
+pub(crate) fn osc_replies(reply: &str) -> Vec<&str> {
+    let mut replies = Vec::new();
+    let mut rest = reply;
+    while let Some(start) = rest.find("\x1B]") {
+        rest = &rest[start + 2..];
+        let end = match (rest.find('\x07'), rest.find("\x1B\\")) {
+            (Some(bel), Some(st)) => bel.min(st),
+            (Some(end), None) | (None, Some(end)) => end,
+            (None, None) => break,
+        };
+        replies.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    replies
+}
+
+/// The background of `COLORFGBG`, set by some terminals to `"foreground;background"` palette
+/// indexes, like `"15;0"`.
+fn colorfgbg_background(value: &str) -> Option<TrueColor> {
+    let index: u8 = value.rsplit(';').next()?.parse().ok()?;
+    if index < 16 {
+        Some(Palette::xterm().index(index))
+    } else {
+        None
+    }
+}
+
+/// The background color given by the `COLORFGBG` environment variable, with the xterm colors.
+pub fn background_from_env() -> Option<TrueColor> {
+    env::var("COLORFGBG")
+        .ok()
+        .and_then(|value| colorfgbg_background(&value))
+}
+
+/// The request of the primary device attributes, and the start of its answer.
+#[cfg(unix)]
+const DEVICE_ATTRIBUTES: &str = "\x1B[c";
+
+/// The position of the answer to the device attributes request, `"\x1B[?62;22c"`.
+#[cfg(unix)]
+fn device_attributes_reply(reply: &[u8]) -> Option<usize> {
+    (0..reply.len()).find(|&start| {
+        reply[start..].starts_with(b"\x1B[?")
+            && reply[start + 3..]
+                .iter()
+                .find(|b| !b.is_ascii_digit() && **b != b';')
+                == Some(&b'c')
+    })
+}
+
+/// Turns the echo and the line buffering of a terminal off, until dropped.
+#[cfg(unix)]
+struct RawMode {
+    fd: RawFd,
+    original: libc::termios,
+}
+
+#[cfg(unix)]
+impl RawMode {
+    fn enable(fd: RawFd) -> io::Result<RawMode> {
+        unsafe {
+            let mut termios: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(fd, &mut termios) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let original = termios;
+            termios.c_lflag &= !(libc::ICANON | libc::ECHO);
+            termios.c_cc[libc::VMIN] = 0;
+            termios.c_cc[libc::VTIME] = 0;
+            if libc::tcsetattr(fd, libc::TCSANOW, &termios) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(RawMode { fd, original })
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        unsafe {
+            libc::tcsetattr(self.fd, libc::TCSANOW, &self.original);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn write_all(fd: RawFd, mut bytes: &[u8]) -> io::Result<()> {
+    while !bytes.is_empty() {
+        let written =
+            unsafe { libc::write(fd, bytes.as_ptr() as *const libc::c_void, bytes.len()) };
+        if written < 0 {
+            let err = io::Error::last_os_error();
+            if err.kind() != io::ErrorKind::Interrupted {
+                return Err(err);
+            }
+        } else {
+            bytes = &bytes[written as usize..];
+        }
+    }
+    Ok(())
+}
+
+/// Whether `fd` is the controlling terminal of the process, and the process is not in its
+/// foreground process group. Changing its settings or writing to it would then stop the
+/// process with `SIGTTOU`.
+#[cfg(unix)]
+fn in_background(fd: RawFd) -> bool {
+    let foreground = unsafe { libc::tcgetpgrp(fd) };
+    // fails for the terminals which are not the controlling one
+    foreground >= 0 && foreground != unsafe { libc::getpgrp() }
+}
+
+/// Writes `request` to the terminal and reads its answer, until the one to the device
+/// attributes request following it. What is read after that answer, like typed keys, is
+/// dropped.
+#[cfg(unix)]
+pub(crate) fn query(fd: RawFd, request: &str, timeout: Duration) -> io::Result<String> {
+    if in_background(fd) {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            "the process is in the background",
+        ));
+    }
+    let _raw_mode = RawMode::enable(fd)?;
+    write_all(fd, format!("{}{}", request, DEVICE_ATTRIBUTES).as_bytes())?;
+
+    let deadline = Instant::now() + timeout;
+    let mut reply = Vec::new();
+    loop {
+        if let Some(end) = device_attributes_reply(&reply) {
+            reply.truncate(end);
+            return Ok(String::from_utf8_lossy(&reply).into_owned());
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "the terminal didn't answer",
+            ));
+        }
+        let millis = (deadline - now).as_millis().clamp(1, i32::MAX as u128) as i32;
+        let mut pollfd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        match unsafe { libc::poll(&mut pollfd, 1, millis) } {
+            0 => continue,
+            ready if ready < 0 => {
+                let err = io::Error::last_os_error();
+                if err.kind() == io::ErrorKind::Interrupted {
+                    continue;
+                }
+                return Err(err);
+            }
+            _ => (),
+        }
+
+        let mut buf = [0u8; 256];
+        let read = unsafe { libc::read(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) };
+        if read < 0 {
+            let err = io::Error::last_os_error();
+            match err.kind() {
+                io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock => continue,
+                _ => return Err(err),
+            }
+        } else if read == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        reply.extend_from_slice(&buf[..read as usize]);
+    }
+}
+
+/// Asks the terminal of `fd` for its background color (OSC 11), waiting at most `timeout` for
+/// its answer. `fd` must be a terminal opened for reading and writing, like `/dev/tty`, which
+/// is put in raw mode for the time of the question.
+///
+/// Nothing else must read the terminal meanwhile, or the answer could be lost, and the keys
+/// typed before the answer arrives are dropped. When `fd` is the controlling terminal and the
+/// process runs in the background, nothing is asked and an error is returned.
+#[cfg(unix)]
+pub fn query_background(fd: RawFd, timeout: Duration) -> io::Result<TrueColor> {
+    let reply = query(fd, "\x1B]11;?\x07", timeout)?;
+    osc_replies(&reply)
+        .into_iter()
+        .filter_map(|body| body.strip_prefix("11;"))
+        .find_map(parse_rgb_spec)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                "the terminal didn't tell its background color",
+            )
+        })
+}
+
+/// Asks the terminal for the palette indexes from 0 to `count`, the ones it doesn't tell
+/// keeping their xterm value, and for its default foreground and background colors (OSC 10
+/// and 11).
+#[cfg(unix)]
+fn query_indexes(fd: RawFd, count: usize, timeout: Duration) -> io::Result<Palette> {
+    let mut request: String = (0..count)
+        .map(|idx| format!("\x1B]4;{};?\x07", idx))
+        .collect();
+    request.push_str("\x1B]10;?\x07\x1B]11;?\x07");
+    let reply = query(fd, &request, timeout)?;
+
+    let mut palette = Palette::with_colors(XTERM_COLORS);
+    let mut answered = false;
+    for body in osc_replies(&reply) {
+        let mut parts = body.splitn(3, ';');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some("4"), Some(index), Some(spec)) => {
+                if let (Ok(index), Some(color)) = (index.parse(), parse_rgb_spec(spec)) {
+                    palette.set_index(index, color);
+                    answered = true;
+                }
+            }
+            (Some("10"), Some(spec), None) => {
+                if let Some(color) = parse_rgb_spec(spec) {
+                    palette.set_foreground(color);
+                }
+            }
+            (Some("11"), Some(spec), None) => {
+                if let Some(color) = parse_rgb_spec(spec) {
+                    palette.set_background(color);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    if answered {
+        Ok(palette)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            "the terminal didn't tell its palette",
+        ))
+    }
+}
+
+/// Asks the terminal of `fd` for the RGB values of its 16 standard colors (OSC 4) and of its
+/// default colors, like `query_background`. The other indexes, and the colors it doesn't
+/// tell, keep their xterm value.
+#[cfg(unix)]
+pub fn query_palette(fd: RawFd, timeout: Duration) -> io::Result<Palette> {
+    query_indexes(fd, 16, timeout)
+}
+
+/// Like `query_palette`, for all the 256 indexes.
+#[cfg(unix)]
+pub fn query_extended_palette(fd: RawFd, timeout: Duration) -> io::Result<Palette> {
+    query_indexes(fd, 256, timeout)
+}
+
+/// The 16 standard colors of the controlling terminal of the process, or the xterm ones when
+/// it doesn't tell them.
+pub fn palette(timeout: Duration) -> Palette {
+    #[cfg(unix)]
+    {
+        if let Ok(palette) = with_tty(|fd| query_palette(fd, timeout)) {
+            return palette;
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = timeout;
+
+    Palette::xterm()
+}
+
+/// Runs `f` on the controlling terminal of the process.
+#[cfg(unix)]
+pub(crate) fn with_tty<T, F>(f: F) -> io::Result<T>
+where
+    F: FnOnce(RawFd) -> io::Result<T>,
+{
+    use std::fs::OpenOptions;
+    use std::os::unix::io::AsRawFd;
+
+    let tty = OpenOptions::new().read(true).write(true).open("/dev/tty")?;
+    f(tty.as_raw_fd())
+}
+
+/// The background color of the terminal: the answer of the controlling terminal of the
+/// process to an OSC 11 question, or else the color given by `COLORFGBG`.
+pub fn background_color(timeout: Duration) -> Option<TrueColor> {
+    #[cfg(unix)]
+    {
+        if let Ok(color) = with_tty(|fd| query_background(fd, timeout)) {
+            return Some(color);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = timeout;
+
+    background_from_env()
+}
+
+/// Whether the background of the terminal is light or dark, when it is known.
+pub fn background_brightness(timeout: Duration) -> Option<Brightness> {
+    background_color(timeout).map(Brightness::of)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rgb_specs() {
+        assert_eq!(
+            Some(TrueColor::new(255, 0, 128)),
+            parse_rgb_spec("rgb:ffff/0000/8080")
+        );
+        assert_eq!(
+            Some(TrueColor::new(255, 17, 0)),
+            parse_rgb_spec("rgb:f/11/000")
+        );
+        assert_eq!(None, parse_rgb_spec("rgb:ffff/0000"));
+        assert_eq!(None, parse_rgb_spec("rgb:fffff/0/0"));
+        assert_eq!(None, parse_rgb_spec("#ffffff"));
+    }
+
+    #[test]
+    fn replies() {
+        assert_eq!(
+            vec!["11;rgb:0/0/0", "4;1;rgb:1/1/1"],
+            osc_replies("\x1B]11;rgb:0/0/0\x1B\\\x1B]4;1;rgb:1/1/1\x07")
+        );
+        assert!(osc_replies("\x1B]11;rgb:0/0").is_empty());
+    }
+
+    #[test]
+    fn colorfgbg() {
+        assert_eq!(Some(TrueColor::new(0, 0, 0)), colorfgbg_background("15;0"));
+        assert_eq!(
+            Some(TrueColor::new(255, 255, 255)),
+            colorfgbg_background("0;default;15")
+        );
+        assert_eq!(None, colorfgbg_background("15;default"));
+        assert_eq!(None, colorfgbg_background("0;16"));
+    }
+
+    #[test]
+    fn brightness() {
+        assert_eq!(Brightness::Dark, Brightness::of(TrueColor::new(0, 0, 0)));
+        assert_eq!(
+            Brightness::Light,
+            Brightness::of(TrueColor::new(0xfd, 0xf6, 0xe3))
+        );
+        assert_eq!(
+            Brightness::Dark,
+            Brightness::of(TrueColor::new(0x28, 0x2a, 0x36))
+        );
+        assert_eq!("dark", Brightness::Dark.pick("light", "dark"));
+    }
+
+    #[cfg(unix)]
+    mod pty {
+        use super::*;
+        use std::ffi::CStr;
+        use std::fs::File;
+        use std::io::{Read, Write};
+        use std::os::unix::io::{AsRawFd, FromRawFd};
+        use std::thread;
+
+        /// A pseudo-terminal: the side of the terminal emulator, and the one of the program.
+        fn open_pty() -> (File, File) {
+            unsafe {
+                let master = libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY);
+                assert!(master >= 0);
+                assert_eq!(0, libc::grantpt(master));
+                assert_eq!(0, libc::unlockpt(master));
+                let name = CStr::from_ptr(libc::ptsname(master)).to_owned();
+                let slave = libc::open(name.as_ptr(), libc::O_RDWR | libc::O_NOCTTY);
+                assert!(slave >= 0);
+                (File::from_raw_fd(master), File::from_raw_fd(slave))
+            }
+        }
+
+        /// Answers the questions ending with a device attributes request with `answer`.
+        fn emulator(mut master: File, answer: &'static str) -> thread::JoinHandle<String> {
+            thread::spawn(move || {
+                let mut question = Vec::new();
+                let mut buf = [0; 64];
+                while !question.ends_with(DEVICE_ATTRIBUTES.as_bytes()) {
+                    let read = master.read(&mut buf).unwrap();
+                    question.extend_from_slice(&buf[..read]);
+                }
+                master.write_all(answer.as_bytes()).unwrap();
+                // keeps the terminal open until the answer is read
+                thread::sleep(Duration::from_millis(200));
+                String::from_utf8(question).unwrap()
+            })
+        }
+
+        #[test]
+        fn background_answered() {
+            let (master, slave) = open_pty();
+            let emulator = emulator(master, "\x1B]11;rgb:fdfd/f6f6/e3e3\x1B\\\x1B[?62;22c");
+
+            let color = query_background(slave.as_raw_fd(), Duration::from_secs(2)).unwrap();
+            assert_eq!(TrueColor::new(0xfd, 0xf6, 0xe3), color);
+            assert_eq!("\x1B]11;?\x07\x1B[c", emulator.join().unwrap());
+        }
+
+        #[test]
+        fn background_unknown() {
+            let (master, slave) = open_pty();
+            let emulator = emulator(master, "\x1B[?1;2c");
+
+            let err = query_background(slave.as_raw_fd(), Duration::from_secs(2)).unwrap_err();
+            assert_eq!(io::ErrorKind::Other, err.kind());
+            emulator.join().unwrap();
+        }
+
+        #[test]
+        fn palette_answered() {
+            use color::Color;
+
+            let (master, slave) = open_pty();
+            let answer = "\x1B]4;1;rgb:dcdc/3232/2f2f\x07\x1B]4;4;rgb:26/8b/d2\x07\
+                          \x1B]11;rgb:0/2b/36\x07\x1B[?62c";
+            let emulator = emulator(master, answer);
+
+            let palette = query_palette(slave.as_raw_fd(), Duration::from_secs(2)).unwrap();
+            assert_eq!(TrueColor::new(0xdc, 0x32, 0x2f), palette.color(Color::Red));
+            assert_eq!(TrueColor::new(0x26, 0x8b, 0xd2), palette.color(Color::Blue));
+            assert_eq!(
+                Palette::xterm().color(Color::Green),
+                palette.color(Color::Green)
+            );
+            assert_eq!(None, palette.foreground());
+            assert_eq!(Some(TrueColor::new(0, 0x2b, 0x36)), palette.background());
+
+            let question = emulator.join().unwrap();
+            assert!(question.starts_with("\x1B]4;0;?\x07\x1B]4;1;?\x07"));
+            assert!(question.ends_with("\x1B]4;15;?\x07\x1B]10;?\x07\x1B]11;?\x07\x1B[c"));
+        }
+
+        #[test]
+        fn palette_unknown() {
+            let (master, slave) = open_pty();
+            let emulator = emulator(master, "\x1B[?1;2c");
+
+            let err = query_palette(slave.as_raw_fd(), Duration::from_secs(2)).unwrap_err();
+            assert_eq!(io::ErrorKind::Other, err.kind());
+            emulator.join().unwrap();
+        }
+
+        #[test]
+        fn no_answer() {
+            let (_master, slave) = open_pty();
+            let err = query_background(slave.as_raw_fd(), Duration::from_millis(50)).unwrap_err();
+            assert_eq!(io::ErrorKind::TimedOut, err.kind());
+        }
+    }
+}
@@ -0,0 +1,365 @@
+//! Helpers for tests.
+//!
+//! `assert_eq_colored!` prints a colored diff of the values it compares:
+//!
+//! ```should_panic
+//! #[macro_use]
+//! extern crate colored;
+//!
+//! # fn main() {
+//! let expected = "first line\nsecond line\n";
+//! let actual = "first line\nsecond lime\n";
+//! // panics with a colored diff of the two texts
+//! assert_eq_colored!(expected, actual);
+//! # }
+//! ```
+//!
+//! `assert_styled_eq!` compares the text and styles displayed by escape sequences, whatever
+//! their order or encoding, and `to_markup` writes them in a readable form for snapshots:
+//!
+//! ```
+//! #[macro_use]
+//! extern crate colored;
+//!
+//! use colored::*;
+//! use colored::testing::to_markup;
+//!
+//! # fn main() {
+//! assert_styled_eq!("\x1B[1;34mtext\x1B[0m", "\x1B[34m\x1B[1mtext\x1B[0m");
+//! assert_eq!("[bold blue]text[/] !", to_markup(&format!("{} !", "text".blue().bold())));
+//! # }
+//! ```
+//!
+//! With the `screen` feature, `Screen` is an in-memory terminal showing what some output looks
+//! like, cell by cell, for the interfaces moving the cursor around.
+
+use std::fmt;
+
+use color::AllColor;
+use diff::Diff;
+use style::ColoredStyle;
+use width::{tokens, Token};
+
+#[cfg(feature = "screen")]
+mod screen;
+
+#[cfg(feature = "screen")]
+pub use self::screen::{Cell, Screen};
+
+/// Like `assert_eq!`, but prints a colored diff of the values when they differ: the lines of
+/// the strings, or the lines of the pretty `Debug` output (`{:#?}`) of the other types.
+///
+/// The diff is plain when colorization is disabled (see `control::SHOULD_COLORIZE`).
+#[macro_export]
+macro_rules! assert_eq_colored {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if !(*left == *right) {
+                    #[allow(unused_imports)]
+                    use $crate::testing::{AsDebugText, AsText};
+                    panic!(
+                        "{}",
+                        $crate::testing::failure_message(
+                            &(&$crate::testing::Printed(left)).as_text(),
+                            &(&$crate::testing::Printed(right)).as_text(),
+                            None
+                        )
+                    );
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if !(*left == *right) {
+                    #[allow(unused_imports)]
+                    use $crate::testing::{AsDebugText, AsText};
+                    panic!(
+                        "{}",
+                        $crate::testing::failure_message(
+                            &(&$crate::testing::Printed(left)).as_text(),
+                            &(&$crate::testing::Printed(right)).as_text(),
+                            Some(format_args!($($arg)+))
+                        )
+                    );
+                }
+            }
+        }
+    };
+}
+
+#[doc(inline)]
+pub use assert_eq_colored;
+
+/// Like `assert_eq!`, for the text and styles displayed by strings holding escape sequences.
+/// `"\x1B[1;34m"` and `"\x1B[34m\x1B[1m"` are the same, and so are a reset and the end of the
+/// string. On failure, the diff of their `to_markup` is printed.
+///
+/// The values can be `ColoredString`s, strings, or anything else displayable.
+#[macro_export]
+macro_rules! assert_styled_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        match ($left.to_string(), $right.to_string()) {
+            (left, right) => {
+                if !$crate::testing::styled_eq(&left, &right) {
+                    panic!(
+                        "{}",
+                        $crate::testing::failure_message(
+                            &$crate::testing::to_markup(&left),
+                            &$crate::testing::to_markup(&right),
+                            None
+                        )
+                    );
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match ($left.to_string(), $right.to_string()) {
+            (left, right) => {
+                if !$crate::testing::styled_eq(&left, &right) {
+                    panic!(
+                        "{}",
+                        $crate::testing::failure_message(
+                            &$crate::testing::to_markup(&left),
+                            &$crate::testing::to_markup(&right),
+                            Some(format_args!($($arg)+))
+                        )
+                    );
+                }
+            }
+        }
+    };
+}
+
+#[doc(inline)]
+pub use assert_styled_eq;
+
+/// A value compared by `assert_eq_colored!`.
+#[doc(hidden)]
+pub struct Printed<'a, T: ?Sized + 'a>(pub &'a T);
+
+/// The text of strings, picked before `AsDebugText` by the method resolution.
+#[doc(hidden)]
+pub trait AsText {
+    fn as_text(&self) -> String;
+}
+
+impl<'a, T: AsRef<str> + ?Sized> AsText for Printed<'a, T> {
+    fn as_text(&self) -> String {
+        String::from(self.0.as_ref())
+    }
+}
+
+/// The text of the other values.
+#[doc(hidden)]
+pub trait AsDebugText {
+    fn as_text(&self) -> String;
+}
+
+impl<'a, 'b, T: fmt::Debug + ?Sized> AsDebugText for &'b Printed<'a, T> {
+    fn as_text(&self) -> String {
+        format!("{:#?}", self.0)
+    }
+}
+
+/// The panic message of `assert_eq_colored!`.
+#[doc(hidden)]
+pub fn failure_message(left: &str, right: &str, message: Option<fmt::Arguments>) -> String {
+    let mut res = String::from("assertion `left == right` failed");
+    if let Some(message) = message {
+        res.push_str(&format!(": {}", message));
+    }
+
+    let diff = Diff::new(left, right).labels("left", "right");
+    if diff.is_empty() {
+        // the lines are the same, the difference is in the line endings
+        res.push_str(&format!("\n  left: {:?}\n right: {:?}", left, right));
+    } else {
+        res.push('\n');
+        res.push_str(&diff.to_string());
+    }
+    res
+}
+
+/// The characters of a string, with the style they are displayed with. The sequences which
+/// aren't SGR ones, or can't be parsed, are ignored.
+fn styled_chars(s: &str) -> Vec<(char, ColoredStyle)> {
+    let mut style = ColoredStyle::new();
+    let mut chars = Vec::new();
+
+    for token in tokens(s) {
+        match token {
+            Token::Char(c) => chars.push((c, style)),
+            Token::Escape(escape) if escape.starts_with("\x1B[") && escape.ends_with('m') => {
+                style = style
+                    .apply_sgr(&escape[2..escape.len() - 1])
+                    .unwrap_or(style);
+            }
+            Token::Escape(_) => (),
+        }
+    }
+    chars
+}
+
+/// Whether two strings display the same text with the same styles, whatever the escape
+/// sequences used for them.
+pub fn styled_eq(left: &str, right: &str) -> bool {
+    styled_chars(left) == styled_chars(right)
+}
+
+fn color_name(color: AllColor) -> String {
+    match color {
+        AllColor::Color(color) => color.to_string().replace(' ', "_"),
+        AllColor::True(color) => color.to_string(),
+        AllColor::Palette(index) => index.to_string(),
+    }
+}
+
+/// The tag of a style, like `"bold blue on red"`.
+fn tag(style: ColoredStyle) -> String {
+    let mut words: Vec<String> = style.styles().iter().map(|s| s.to_string()).collect();
+    if let Some(fg) = style.fgcolor() {
+        words.push(color_name(fg));
+    }
+    if let Some(bg) = style.bgcolor() {
+        words.push(String::from("on"));
+        words.push(color_name(bg));
+    }
+    if let Some(underline) = style.ulcolor() {
+        words.push(String::from("under"));
+        words.push(color_name(underline));
+    }
+    words.join(" ")
+}
+
+/// Writes the text and styles of a string holding escape sequences as markup, like
+/// `"[bold blue]text[/] and [on #ff8700]more[/]"`, for snapshot files or failure messages.
+///
+/// The markup is the one of `markup::Template`, which can parse it back.
+pub fn to_markup(s: &str) -> String {
+    let mut res = String::new();
+    let mut current = ColoredStyle::new();
+
+    for (c, style) in styled_chars(s) {
+        if style != current {
+            if current != ColoredStyle::new() {
+                res.push_str("[/]");
+            }
+            if style != ColoredStyle::new() {
+                res.push_str(&format!("[{}]", tag(style)));
+            }
+            current = style;
+        }
+        if "[]{}\\".contains(c) {
+            res.push('\\');
+        }
+        res.push(c);
+    }
+    if current != ColoredStyle::new() {
+        res.push_str("[/]");
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use width::strip_escapes;
+
+    #[test]
+    fn equal_values() {
+        assert_eq_colored!("a\nb", String::from("a\nb"));
+        assert_eq_colored!(vec![1, 2], vec![1, 2], "with a {}", "message");
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion `left == right` failed: in the second case")]
+    fn different_values() {
+        assert_eq_colored!(1, 2, "in the {} case", "second");
+    }
+
+    #[test]
+    fn message_of_strings() {
+        assert_eq!(
+            "assertion `left == right` failed\n\
+             --- left\n+++ right\n@@ -1,2 +1,2 @@\n a\n-b\n+c",
+            strip_escapes(&failure_message("a\nb\n", "a\nc\n", None))
+        );
+        assert_eq!(
+            "assertion `left == right` failed\n  left: \"a\\n\"\n right: \"a\"",
+            failure_message("a\n", "a", None)
+        );
+    }
+
+    #[test]
+    fn texts() {
+        let string = String::from("a\nb");
+        assert_eq!("a\nb", Printed(&string).as_text());
+        assert_eq!("a\nb", Printed("a\nb").as_text());
+        assert_eq!("[\n    1,\n]", (&Printed(&vec![1])).as_text());
+    }
+
+    #[test]
+    fn styled_equality() {
+        use Colorize;
+
+        assert!(styled_eq("\x1B[1;34mtext\x1B[0m", "\x1B[34;1mtext"));
+        assert!(styled_eq(
+            &"text".blue().bold().on_red().to_string(),
+            "\x1B[41m\x1B[1m\x1B[34mtext\x1B[m"
+        ));
+        assert!(styled_eq("\x1B[1m\x1B[0mtext", "text"));
+        assert!(!styled_eq("\x1B[1mtext", "text"));
+        assert!(!styled_eq("\x1B[1mte\x1B[0mxt", "\x1B[1mtext"));
+        assert!(!styled_eq("\x1B[4;58;5;1mx", "\x1B[4;58;5;4mx"));
+        assert!(styled_eq("\x1B[4;58;5;1mx\x1B[59my", "\x1B[4;58;5;1mx\x1B[0;4my"));
+    }
+
+    #[test]
+    #[should_panic(expected = "dimmed")]
+    fn styled_difference() {
+        assert_styled_eq!("\x1B[1mtext\x1B[0m", "\x1B[2mtext\x1B[0m");
+    }
+
+    #[test]
+    fn markup() {
+        use color::Color;
+        use Colorize;
+
+        assert_eq!("plain \\[x\\]", to_markup("plain [x]"));
+        assert_eq!(
+            "a [bold blue on red]b[/][bright_red on #ff8700]c[/][208 on 17]d[/]",
+            to_markup(&format!(
+                "a {}{}{}",
+                "b".blue().on_red().bold(),
+                "c".bright_red().on_true_color(255, 135, 0),
+                "d".palette(208).on_palette(17)
+            ))
+        );
+        assert_eq!(
+            "[red]a [/][bold red]b[/][red] c[/]",
+            to_markup(&format!("a {} c", "b".bold()).color(Color::Red).to_string())
+        );
+        assert_eq!(
+            "[underline under 1]x[/]",
+            to_markup("\x1B[4;58;5;1mx\x1B[0m")
+        );
+    }
+
+    #[test]
+    fn markup_roundtrip() {
+        use markup::Template;
+        use Colorize;
+
+        let text = format!(
+            "x {} {{y}} {}",
+            "[b]".curly_underline().on_bright_blue().underline_color("red"),
+            "\\".true_color(1, 2, 3)
+        );
+        let rendered = Template::parse(&to_markup(&text)).unwrap().render(|_| None);
+        assert_styled_eq!(text, rendered);
+    }
+}
@@ -0,0 +1,462 @@
+//! An in-memory terminal, to check what some output looks like cell by cell.
+
+use std::fmt;
+
+use color::AllColor;
+use style::{ColoredStyle, Styles};
+use width::{char_width, tokens, Token};
+
+/// A character on the screen, with its colors and styles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    c: char,
+    style: ColoredStyle,
+    /// The second half of a wide character.
+    continuation: bool,
+}
+
+impl Cell {
+    fn blank(style: ColoredStyle) -> Cell {
+        Cell {
+            c: ' ',
+            style,
+            continuation: false,
+        }
+    }
+
+    /// The character of the cell, a space for the blank ones and for the second cell of the
+    /// wide characters.
+    pub fn char(&self) -> char {
+        self.c
+    }
+
+    pub fn style(&self) -> ColoredStyle {
+        self.style
+    }
+
+    pub fn fgcolor(&self) -> Option<AllColor> {
+        self.style.fgcolor()
+    }
+
+    pub fn bgcolor(&self) -> Option<AllColor> {
+        self.style.bgcolor()
+    }
+
+    /// Whether the cell is displayed with the given style.
+    pub fn has(&self, style: Styles) -> bool {
+        self.style.has(style)
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Cell {
+        Cell::blank(ColoredStyle::new())
+    }
+}
+
+/// A grid of cells fed with text holding escape sequences, like a terminal.
+///
+/// It understands the SGR sequences, the cursor moves (`CUU`, `CUD`, `CUF`, `CUB`, `CNL`,
+/// `CPL`, `CHA`, `CUP`), the erasures (`ED`, `EL`), the saving and restoring of the cursor,
+/// its visibility and the alternate screen. The other sequences are ignored. A newline moves
+/// to the start of the next line, like a terminal in its usual mode, and the screen scrolls
+/// when the cursor goes past its last line.
+///
+/// # Example
+/// ```
+/// use colored::*;
+/// use colored::testing::Screen;
+///
+/// let mut screen = Screen::new(20, 2);
+/// screen.feed(&format!("{} done\n", "ok".green().bold()));
+///
+/// assert_eq!("ok done", screen.line(0));
+/// assert_eq!(Some(AllColor::Color(Color::Green)), screen.cell(0, 1).unwrap().fgcolor());
+/// assert!(screen.cell(0, 3).unwrap().fgcolor().is_none());
+/// assert_eq!((1, 0), screen.cursor());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Screen {
+    width: usize,
+    height: usize,
+    cells: Vec<Vec<Cell>>,
+    row: usize,
+    col: usize,
+    /// The last column was written, the next character goes to the next line.
+    wrap_pending: bool,
+    style: ColoredStyle,
+    saved: (usize, usize, ColoredStyle),
+    cursor_visible: bool,
+    /// The main screen while the alternate one is shown, with its cursor.
+    main: Option<(Vec<Vec<Cell>>, usize, usize, ColoredStyle)>,
+}
+
+impl Screen {
+    /// A blank screen of `width` columns and `height` lines, at least one of each.
+    pub fn new(width: usize, height: usize) -> Screen {
+        let width = width.max(1);
+        let height = height.max(1);
+        Screen {
+            width,
+            height,
+            cells: vec![vec![Cell::default(); width]; height],
+            row: 0,
+            col: 0,
+            wrap_pending: false,
+            style: ColoredStyle::new(),
+            saved: (0, 0, ColoredStyle::new()),
+            cursor_visible: true,
+            main: None,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The line and the column of the cursor, from 0.
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.row, self.col)
+    }
+
+    pub fn cursor_visible(&self) -> bool {
+        self.cursor_visible
+    }
+
+    /// Whether the alternate screen is shown.
+    pub fn is_alternate(&self) -> bool {
+        self.main.is_some()
+    }
+
+    /// The cell at the given line and column, from 0.
+    pub fn cell(&self, row: usize, col: usize) -> Option<&Cell> {
+        self.cells.get(row).and_then(|line| line.get(col))
+    }
+
+    /// The text of a line, without its trailing spaces.
+    pub fn line(&self, row: usize) -> String {
+        self.cells.get(row).map_or_else(String::new, |line| {
+            let text: String = line
+                .iter()
+                .filter(|cell| !cell.continuation)
+                .map(|cell| cell.c)
+                .collect();
+            String::from(text.trim_end())
+        })
+    }
+
+    /// The text of the screen, without the trailing spaces of its lines nor its trailing
+    /// blank lines.
+    pub fn contents(&self) -> String {
+        let lines: Vec<String> = (0..self.height).map(|row| self.line(row)).collect();
+        String::from(lines.join("\n").trim_end_matches('\n'))
+    }
+
+    /// Writes `text` at the cursor, interpreting its control characters and escape sequences.
+    pub fn feed(&mut self, text: &str) {
+        for token in tokens(text) {
+            match token {
+                Token::Char(c) => self.put(c),
+                Token::Escape(escape) => self.escape(escape),
+            }
+        }
+    }
+
+    fn put(&mut self, c: char) {
+        match c {
+            '\n' => {
+                self.col = 0;
+                self.line_feed();
+            }
+            '\r' => {
+                self.col = 0;
+                self.wrap_pending = false;
+            }
+            '\t' => {
+                self.col = ((self.col / 8 + 1) * 8).min(self.width - 1);
+                self.wrap_pending = false;
+            }
+            '\x08' => {
+                self.col = self.col.saturating_sub(1);
+                self.wrap_pending = false;
+            }
+            _ => {
+                let width = char_width(c);
+                if width == 0 {
+                    return;
+                }
+                if self.wrap_pending || self.col + width > self.width {
+                    self.col = 0;
+                    self.line_feed();
+                }
+
+                let cell = Cell {
+                    c,
+                    style: self.style,
+                    continuation: false,
+                };
+                self.cells[self.row][self.col] = cell;
+                if width == 2 && self.col + 1 < self.width {
+                    self.cells[self.row][self.col + 1] = Cell {
+                        c: ' ',
+                        continuation: true,
+                        ..cell
+                    };
+                }
+
+                if self.col + width >= self.width {
+                    self.col = self.width - 1;
+                    self.wrap_pending = true;
+                } else {
+                    self.col += width;
+                }
+            }
+        }
+    }
+
+    /// Moves the cursor down, scrolling at the bottom of the screen.
+    fn line_feed(&mut self) {
+        self.wrap_pending = false;
+        if self.row + 1 < self.height {
+            self.row += 1;
+        } else {
+            self.cells.remove(0);
+            self.cells.push(vec![Cell::blank(self.blank_style()); self.width]);
+        }
+    }
+
+    /// The style of the erased cells: only the background color stays, like in most terminals.
+    fn blank_style(&self) -> ColoredStyle {
+        match self.style.bgcolor() {
+            Some(bg) => ColoredStyle::new().bg(bg),
+            None => ColoredStyle::new(),
+        }
+    }
+
+    fn move_to(&mut self, row: usize, col: usize) {
+        self.row = row.min(self.height - 1);
+        self.col = col.min(self.width - 1);
+        self.wrap_pending = false;
+    }
+
+    fn erase(&mut self, row: usize, cols: std::ops::Range<usize>) {
+        let blank = Cell::blank(self.blank_style());
+        for cell in &mut self.cells[row][cols] {
+            *cell = blank;
+        }
+    }
+
+    fn escape(&mut self, escape: &str) {
+        match escape {
+            "\x1B7" => self.saved = (self.row, self.col, self.style),
+            "\x1B8" => self.restore(),
+            _ => {
+                if let Some(csi) = escape.strip_prefix("\x1B[") {
+                    let (params, command) = csi.split_at(csi.len() - 1);
+                    self.csi(params, command);
+                }
+            }
+        }
+    }
+
+    fn restore(&mut self) {
+        let (row, col, style) = self.saved;
+        self.move_to(row, col);
+        self.style = style;
+    }
+
+    fn csi(&mut self, params: &str, command: &str) {
+        if let Some(private) = params.strip_prefix('?') {
+            let on = match command {
+                "h" => true,
+                "l" => false,
+                _ => return,
+            };
+            for mode in private.split(';') {
+                match mode {
+                    "25" => self.cursor_visible = on,
+                    "1049" => self.alternate(on),
+                    _ => (),
+                }
+            }
+            return;
+        }
+
+        let args: Vec<usize> = params
+            .split(';')
+            .map(|param| param.parse().unwrap_or(0))
+            .collect();
+        // the count of the moves, 1 when missing or 0
+        let n = args[0].max(1);
+        let (row, col) = (self.row, self.col);
+
+        match command {
+            "m" => self.style = self.style.apply_sgr(params).unwrap_or(self.style),
+            "A" => self.move_to(row.saturating_sub(n), col),
+            "B" => self.move_to(row + n, col),
+            "C" => self.move_to(row, col + n),
+            "D" => self.move_to(row, col.saturating_sub(n)),
+            "E" => self.move_to(row + n, 0),
+            "F" => self.move_to(row.saturating_sub(n), 0),
+            "G" => self.move_to(row, n - 1),
+            "H" | "f" => {
+                let col = args.get(1).map_or(1, |&col| col.max(1));
+                self.move_to(n - 1, col - 1);
+            }
+            "J" => match args[0] {
+                0 => {
+                    self.erase(row, col..self.width);
+                    for row in row + 1..self.height {
+                        self.erase(row, 0..self.width);
+                    }
+                }
+                1 => {
+                    for row in 0..row {
+                        self.erase(row, 0..self.width);
+                    }
+                    self.erase(row, 0..col + 1);
+                }
+                2 | 3 => {
+                    for row in 0..self.height {
+                        self.erase(row, 0..self.width);
+                    }
+                }
+                _ => (),
+            },
+            "K" => match args[0] {
+                0 => self.erase(row, col..self.width),
+                1 => self.erase(row, 0..col + 1),
+                2 => self.erase(row, 0..self.width),
+                _ => (),
+            },
+            "s" => self.saved = (row, col, self.style),
+            "u" => self.restore(),
+            _ => (),
+        }
+    }
+
+    fn alternate(&mut self, on: bool) {
+        if on && self.main.is_none() {
+            let blank = vec![vec![Cell::default(); self.width]; self.height];
+            let main = std::mem::replace(&mut self.cells, blank);
+            self.main = Some((main, self.row, self.col, self.style));
+        } else if !on {
+            if let Some((main, row, col, style)) = self.main.take() {
+                self.cells = main;
+                self.move_to(row, col);
+                self.style = style;
+            }
+        }
+    }
+}
+
+impl fmt::Write for Screen {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.feed(s);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use color::Color;
+    use Colorize;
+
+    fn fg(screen: &Screen, row: usize, col: usize) -> Option<AllColor> {
+        screen.cell(row, col).unwrap().fgcolor()
+    }
+
+    #[test]
+    fn nested_colors() {
+        let mut screen = Screen::new(10, 1);
+        screen.feed(&format!("a {} c", "b".red().bold()).on_blue().to_string());
+
+        assert_eq!("a b c", screen.line(0));
+        for col in 0..5 {
+            assert_eq!(
+                Some(AllColor::Color(Color::Blue)),
+                screen.cell(0, col).unwrap().bgcolor()
+            );
+        }
+        assert_eq!(Some(AllColor::Color(Color::Red)), fg(&screen, 0, 2));
+        assert!(screen.cell(0, 2).unwrap().has(Styles::Bold));
+        assert!(!screen.cell(0, 4).unwrap().has(Styles::Bold));
+        assert_eq!(None, screen.cell(0, 5).unwrap().bgcolor());
+    }
+
+    #[test]
+    fn wrapping_and_scrolling() {
+        let mut screen = Screen::new(3, 2);
+        screen.feed("abcdefg");
+        assert_eq!("def\ng", screen.contents());
+        assert_eq!((1, 1), screen.cursor());
+
+        screen.feed("\nxyz");
+        assert_eq!("g\nxyz", screen.contents());
+        assert_eq!((1, 2), screen.cursor());
+    }
+
+    #[test]
+    fn wide_characters() {
+        let mut screen = Screen::new(4, 2);
+        screen.feed("a日本");
+        assert_eq!("a日\n本", screen.contents());
+        assert_eq!('日', screen.cell(0, 1).unwrap().char());
+        assert_eq!(' ', screen.cell(0, 2).unwrap().char());
+    }
+
+    #[test]
+    fn cursor_moves() {
+        let mut screen = Screen::new(10, 3);
+        screen.feed("hello\x1B[2DXY\x1B[2;3H*\x1B[A!\x1B[3G?\x1B[2E#");
+        assert_eq!("he?!Y\n  *\n#", screen.contents());
+
+        screen.feed("\x1B[1;1H\x1B7\x1B[3;5H\x1B8%");
+        assert_eq!((0, 1), screen.cursor());
+        assert_eq!("%e?!Y", screen.line(0));
+    }
+
+    #[test]
+    fn erasing() {
+        let mut screen = Screen::new(5, 2);
+        screen.feed("abcde\nfghij\x1B[1;3H\x1B[K");
+        assert_eq!("ab\nfghij", screen.contents());
+        screen.feed("\x1B[1K");
+        assert_eq!("\nfghij", screen.contents());
+        screen.feed("\x1B[41m\x1B[2J");
+        assert_eq!("", screen.contents());
+        assert_eq!(
+            Some(AllColor::Color(Color::Red)),
+            screen.cell(1, 4).unwrap().bgcolor()
+        );
+    }
+
+    #[test]
+    fn modes() {
+        let mut screen = Screen::new(5, 2);
+        screen.feed("main\x1B[?25l");
+        assert!(!screen.cursor_visible());
+
+        screen.feed("\x1B[?1049h\x1B[Halt");
+        assert!(screen.is_alternate());
+        assert_eq!("alt", screen.contents());
+
+        screen.feed("\x1B[?1049l\x1B[?25h");
+        assert!(!screen.is_alternate());
+        assert!(screen.cursor_visible());
+        assert_eq!("main", screen.contents());
+        assert_eq!((0, 4), screen.cursor());
+    }
+
+    #[test]
+    fn hyperlinks_ignored() {
+        let mut screen = Screen::new(10, 1);
+        screen.feed("\x1B]8;;https://a\x1B\\link\x1B]8;;\x1B\\");
+        assert_eq!("link", screen.contents());
+    }
+}
@@ -0,0 +1,217 @@
+//! Semantic roles, like `error` or `path`, mapped to styles by a swappable theme.
+//!
+//! ```
+//! use colored::*;
+//!
+//! println!("{}: {} not found", "error".role("error"), "Cargo.toml".role("path"));
+//!
+//! // users can re-theme the roles
+//! let mut theme = theme::Theme::light();
+//! theme.set("path", ColoredStyle::new().color(Color::Green).underline());
+//! theme::set_theme(theme);
+//! ```
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use color::{AllColor, Color};
+use cvd;
+use style::ColoredStyle;
+
+/// A set of named roles and the style to use for each of them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Theme {
+    styles: HashMap<String, ColoredStyle>,
+}
+
+lazy_static! {
+    static ref THEME: RwLock<Theme> = RwLock::new(Theme::dark());
+}
+
+thread_local! {
+    static SCOPED_THEMES: RefCell<Vec<Theme>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Replaces the theme used by `Colorize::role`, for all threads.
+pub fn set_theme(theme: Theme) {
+    *THEME.write().unwrap_or_else(|e| e.into_inner()) = theme;
+}
+
+/// The theme currently used by `Colorize::role` on this thread.
+pub fn current_theme() -> Theme {
+    SCOPED_THEMES.with(|scoped| match scoped.borrow().last() {
+        Some(theme) => theme.clone(),
+        None => THEME.read().unwrap_or_else(|e| e.into_inner()).clone(),
+    })
+}
+
+/// Uses `theme` instead of the global one while `f` runs on this thread.
+///
+/// # Example
+/// ```
+/// use colored::*;
+///
+/// theme::with_theme(theme::Theme::light(), || {
+///     println!("{}", "done".role("heading"));
+/// });
+/// ```
+pub fn with_theme<F, R>(theme: Theme, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    struct Restore;
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SCOPED_THEMES.with(|scoped| scoped.borrow_mut().pop());
+        }
+    }
+
+    SCOPED_THEMES.with(|scoped| scoped.borrow_mut().push(theme));
+    let _restore = Restore;
+    f()
+}
+
+/// The style of `role` in the current theme, if it has one.
+pub fn style_for(role: &str) -> Option<ColoredStyle> {
+    SCOPED_THEMES.with(|scoped| match scoped.borrow().last() {
+        Some(theme) => theme.get(role),
+        None => THEME.read().unwrap_or_else(|e| e.into_inner()).get(role),
+    })
+}
+
+impl Theme {
+    /// An empty theme: every role is rendered as plain text.
+    pub fn new() -> Theme {
+        Theme::default()
+    }
+
+    /// The default theme, for terminals with a dark background.
+    pub fn dark() -> Theme {
+        Theme::new()
+            .with_role("error", ColoredStyle::new().color(Color::BrightRed).bold())
+            .with_role("warning", ColoredStyle::new().color(Color::BrightYellow))
+            .with_role("hint", ColoredStyle::new().color(Color::BrightCyan))
+            .with_role("path", ColoredStyle::new().color(Color::BrightBlue).underline())
+            .with_role("number", ColoredStyle::new().color(Color::BrightMagenta))
+            .with_role("heading", ColoredStyle::new().color(Color::BrightWhite).bold().underline())
+    }
+
+    /// A theme for terminals with a light background, avoiding the colors hard to read on white.
+    pub fn light() -> Theme {
+        Theme::new()
+            .with_role("error", ColoredStyle::new().color(Color::Red).bold())
+            .with_role("warning", ColoredStyle::new().palette(130))
+            .with_role("hint", ColoredStyle::new().color(Color::Blue))
+            .with_role("path", ColoredStyle::new().color(Color::Blue).underline())
+            .with_role("number", ColoredStyle::new().color(Color::Magenta))
+            .with_role("heading", ColoredStyle::new().color(Color::Black).bold().underline())
+    }
+
+    /// A theme with the colors of the Okabe-Ito palette, which people with a color vision
+    /// deficiency can tell apart, readable on dark and light backgrounds.
+    pub fn okabe_ito() -> Theme {
+        Theme::new()
+            .with_role("error", ColoredStyle::new().fg(AllColor::True(cvd::VERMILLION)).bold())
+            .with_role("warning", ColoredStyle::new().fg(AllColor::True(cvd::ORANGE)))
+            .with_role("hint", ColoredStyle::new().fg(AllColor::True(cvd::SKY_BLUE)))
+            .with_role("path", ColoredStyle::new().fg(AllColor::True(cvd::BLUE)).underline())
+            .with_role("number", ColoredStyle::new().fg(AllColor::True(cvd::REDDISH_PURPLE)))
+            .with_role("heading", ColoredStyle::new().bold().underline())
+    }
+
+    /// Sets the style of `role`, returning its previous style.
+    pub fn set<S: Into<String>>(&mut self, role: S, style: ColoredStyle) -> Option<ColoredStyle> {
+        self.styles.insert(role.into(), style)
+    }
+
+    /// Same as `Theme::set`, but can be chained.
+    pub fn with_role<S: Into<String>>(mut self, role: S, style: ColoredStyle) -> Theme {
+        self.set(role, style);
+        self
+    }
+
+    pub fn get(&self, role: &str) -> Option<ColoredStyle> {
+        self.styles.get(role).cloned()
+    }
+
+    pub fn remove(&mut self, role: &str) -> Option<ColoredStyle> {
+        self.styles.remove(role)
+    }
+
+    /// The roles defined by this theme, in no particular order.
+    pub fn roles(&self) -> impl Iterator<Item = &str> {
+        self.styles.keys().map(|role| role.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Colorize;
+
+    #[test]
+    fn builtin_themes_define_the_same_roles() {
+        let mut dark: Vec<&str> = Vec::new();
+        let mut light: Vec<&str> = Vec::new();
+        let mut okabe_ito: Vec<&str> = Vec::new();
+        let (dark_theme, light_theme) = (Theme::dark(), Theme::light());
+        let okabe_ito_theme = Theme::okabe_ito();
+        dark.extend(dark_theme.roles());
+        light.extend(light_theme.roles());
+        okabe_ito.extend(okabe_ito_theme.roles());
+        dark.sort();
+        light.sort();
+        okabe_ito.sort();
+
+        assert_eq!(
+            vec!["error", "heading", "hint", "number", "path", "warning"],
+            dark
+        );
+        assert_eq!(dark, light);
+        assert_eq!(dark, okabe_ito);
+    }
+
+    #[test]
+    fn set_returns_previous_style() {
+        let mut theme = Theme::new();
+        let style = ColoredStyle::new().color(Color::Red);
+
+        assert_eq!(None, theme.set("error", style));
+        assert_eq!(Some(style), theme.set("error", ColoredStyle::new()));
+        assert_eq!(Some(ColoredStyle::new()), theme.remove("error"));
+        assert_eq!(None, theme.get("error"));
+    }
+
+    #[test]
+    fn with_theme_is_scoped() {
+        let theme = Theme::new().with_role("error", ColoredStyle::new().color(Color::Green));
+
+        with_theme(theme.clone(), || {
+            assert_eq!(theme, current_theme());
+            assert_eq!("x".green(), "x".role("error"));
+            with_theme(Theme::new(), || {
+                assert_eq!(None, style_for("error"));
+            });
+            assert_eq!("x".green(), "x".role("error"));
+        });
+        assert_ne!(theme, current_theme());
+    }
+
+    #[test]
+    fn role_keeps_existing_styles() {
+        let theme = Theme::new().with_role("error", ColoredStyle::new().color(Color::Red).bold());
+
+        with_theme(theme, || {
+            assert_eq!("x".italic().red().bold(), "x".italic().blue().role("error"));
+        });
+    }
+
+    #[test]
+    fn unknown_role_is_plain() {
+        with_theme(Theme::dark(), || {
+            assert!("x".role("zorglub").is_plain());
+        });
+    }
+}
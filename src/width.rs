@@ -0,0 +1,210 @@
+//! On-screen width of strings holding escape sequences, like the output of this crate.
+//!
+//! ```
+//! use colored::*;
+//!
+//! let name = format!("{} {}", "東京".bold(), "tower".blue());
+//! assert_eq!(10, colored::width::visible_width(&name));
+//! ```
+
+use unicode_width::UnicodeWidthChar;
+
+/// A piece of a string: an escape sequence, or a character.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Token<'a> {
+    Escape(&'a str),
+    Char(char),
+}
+
+/// Splits a string into escape sequences and characters.
+pub(crate) struct Tokens<'a> {
+    rest: &'a str,
+}
+
+pub(crate) fn tokens(s: &str) -> Tokens<'_> {
+    Tokens { rest: s }
+}
+
+/// The length of the escape sequence at the start of `s`: CSI (`"\x1B[1;31m"`), OSC
+/// (`"\x1B]8;;url\x1B\\"`), or another escape sequence (`"\x1B(B"`).
+fn escape_len(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    if bytes.first() != Some(&0x1B) || bytes.len() < 2 {
+        return None;
+    }
+
+    match bytes[1] {
+        b'[' => bytes[2..]
+            .iter()
+            .position(|b| (0x40..=0x7E).contains(b))
+            .map(|idx| idx + 3),
+        b']' => {
+            let mut idx = 2;
+            while idx < bytes.len() {
+                match bytes[idx] {
+                    0x07 => return Some(idx + 1),
+                    0x1B if bytes.get(idx + 1) == Some(&b'\\') => return Some(idx + 2),
+                    _ => idx += 1,
+                }
+            }
+            None
+        }
+        // intermediate bytes, then a final one
+        _ => bytes[1..]
+            .iter()
+            .position(|b| !(0x20..=0x2F).contains(b))
+            .filter(|&idx| (0x30..=0x7E).contains(&bytes[1 + idx]))
+            .map(|idx| idx + 2),
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        if let Some(len) = escape_len(self.rest) {
+            let (escape, rest) = self.rest.split_at(len);
+            self.rest = rest;
+            return Some(Token::Escape(escape));
+        }
+
+        let c = self.rest.chars().next()?;
+        self.rest = &self.rest[c.len_utf8()..];
+        Some(Token::Char(c))
+    }
+}
+
+/// The width of a character in a terminal: 2 for the wide CJK characters and emojis, 0 for the
+/// combining marks, the zero width joiner and the control characters.
+pub(crate) fn char_width(c: char) -> usize {
+    c.width().unwrap_or(0)
+}
+
+/// The number of columns `s` takes in a terminal, its escape sequences being ignored.
+pub fn visible_width(s: &str) -> usize {
+    tokens(s)
+        .map(|token| match token {
+            Token::Char(c) => char_width(c),
+            Token::Escape(_) => 0,
+        })
+        .sum()
+}
+
+/// `s` without its escape sequences.
+pub fn strip_escapes(s: &str) -> String {
+    tokens(s)
+        .filter_map(|token| match token {
+            Token::Char(c) => Some(c),
+            Token::Escape(_) => None,
+        })
+        .collect()
+}
+
+/// Shortens `s` to at most `width` columns, ending it with `ellipsis` when something was cut.
+///
+/// The escape sequences are all kept, including the ones after the cut, so that the styles
+/// opened before it are still closed.
+pub fn truncate(s: &str, width: usize, ellipsis: &str) -> String {
+    if visible_width(s) <= width {
+        return String::from(s);
+    }
+
+    let ellipsis_width = visible_width(ellipsis);
+    let (mut available, ellipsis) = if ellipsis_width <= width {
+        (width - ellipsis_width, ellipsis)
+    } else {
+        (width, "")
+    };
+
+    let mut res = String::with_capacity(s.len());
+    let mut cut = false;
+    for token in tokens(s) {
+        match token {
+            Token::Escape(escape) => res.push_str(escape),
+            Token::Char(_) if cut => (),
+            Token::Char(c) => {
+                let c_width = char_width(c);
+                if c_width <= available {
+                    available -= c_width;
+                    res.push(c);
+                } else {
+                    cut = true;
+                    res.push_str(ellipsis);
+                }
+            }
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Colorize;
+
+    #[test]
+    fn tokens_of_escapes() {
+        let s = "a\x1B[1;31mb\x1B]8;;http://x\x1B\\c\x1B]0;title\x07\x1B(B";
+        let escapes: Vec<_> = tokens(s)
+            .filter_map(|token| match token {
+                Token::Escape(escape) => Some(escape),
+                Token::Char(_) => None,
+            })
+            .collect();
+
+        assert_eq!(
+            vec![
+                "\x1B[1;31m",
+                "\x1B]8;;http://x\x1B\\",
+                "\x1B]0;title\x07",
+                "\x1B(B",
+            ],
+            escapes
+        );
+        assert_eq!("abc", strip_escapes(s));
+    }
+
+    #[test]
+    fn widths() {
+        assert_eq!(0, visible_width(""));
+        assert_eq!(5, visible_width("hello"));
+        assert_eq!(4, visible_width("日本"));
+        assert_eq!(2, visible_width("🦀"));
+        // "e" and a combining acute accent
+        assert_eq!(1, visible_width("e\u{301}"));
+        // a zero width joiner between two emojis
+        assert_eq!(4, visible_width("👩\u{200D}🔬"));
+        assert_eq!(2, visible_width("\x1B[31m日\x1B[0m"));
+    }
+
+    #[test]
+    fn nested_escapes() {
+        let nested = format!("a {} c", "b".red().bold()).on_blue();
+        assert_eq!(5, visible_width(&nested.to_string()));
+        assert_eq!(5, nested.visible_width());
+    }
+
+    #[test]
+    fn truncation() {
+        assert_eq!("hello", truncate("hello", 5, "…"));
+        assert_eq!("hell…", truncate("hello world", 5, "…"));
+        assert_eq!("he...", truncate("hello world", 5, "..."));
+        assert_eq!("he", truncate("hello world", 2, "..."));
+        // the wide character doesn't fit
+        assert_eq!("日…", truncate("日本語", 4, "…"));
+        assert_eq!(
+            "\x1B[31mhe…\x1B[0m\x1B[1m\x1B[0m",
+            truncate("\x1B[31mhello\x1B[0m\x1B[1mworld\x1B[0m", 3, "…")
+        );
+    }
+
+    #[test]
+    fn truncate_colored_string() {
+        let colored = format!("a {} c", "bbb".bold()).red();
+        let truncated = colored.truncate_to_width(4, "…");
+
+        assert_eq!(4, truncated.visible_width());
+        assert_eq!(format!("a {}", "b…".bold()).red(), truncated);
+        assert_eq!(colored, colored.truncate_to_width(10, "…"));
+    }
+}
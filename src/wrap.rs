@@ -0,0 +1,427 @@
+//! Word wrapping of strings holding escape sequences, like the output of this crate.
+//!
+//! Every line is styled on its own: the styles active at its start are opened again, and
+//! they are reset at its end, so that nothing bleeds into the margins or the next output.
+//!
+//! ```
+//! use colored::*;
+//! use colored::wrap::{wrap, Justify, WrapOptions};
+//!
+//! let help = format!("{} the files, {}", "Lists".bold(), "ignoring the hidden ones".dimmed());
+//! let options = WrapOptions::new(20).indent(2).hanging_indent(2).justify(Justify::Full);
+//! for line in wrap(&help, &options) {
+//!     println!("{}", line);
+//! }
+//! ```
+
+use std::fmt;
+
+use width::{char_width, tokens, Token};
+
+/// How the words are laid out in their line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Justify {
+    Left,
+    Right,
+    Center,
+    /// Spreads the words over the whole width, except in the last line of a paragraph.
+    Full,
+}
+
+/// The width of the lines and their layout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WrapOptions {
+    width: usize,
+    indent: usize,
+    hanging_indent: usize,
+    justify: Justify,
+}
+
+impl WrapOptions {
+    /// Lines of at most `width` columns, indentation included.
+    pub fn new(width: usize) -> WrapOptions {
+        WrapOptions {
+            width,
+            indent: 0,
+            hanging_indent: 0,
+            justify: Justify::Left,
+        }
+    }
+
+    /// Indents all the lines by `columns`.
+    pub fn indent(self, columns: usize) -> WrapOptions {
+        WrapOptions {
+            indent: columns,
+            ..self
+        }
+    }
+
+    /// Indents the lines after the first one of each paragraph by `columns` more.
+    pub fn hanging_indent(self, columns: usize) -> WrapOptions {
+        WrapOptions {
+            hanging_indent: columns,
+            ..self
+        }
+    }
+
+    pub fn justify(self, justify: Justify) -> WrapOptions {
+        WrapOptions { justify, ..self }
+    }
+
+    fn indent_of(&self, line_in_paragraph: usize) -> usize {
+        if line_in_paragraph == 0 {
+            self.indent
+        } else {
+            self.indent + self.hanging_indent
+        }
+    }
+
+    /// The room left for the words, never less than a column.
+    fn available(&self, line_in_paragraph: usize) -> usize {
+        self.width
+            .saturating_sub(self.indent_of(line_in_paragraph))
+            .max(1)
+    }
+}
+
+/// The SGR sequences and the link active at some point of the text.
+#[derive(Clone, Debug, Default)]
+struct State<'a> {
+    sgr: Vec<&'a str>,
+    link: Option<&'a str>,
+}
+
+impl<'a> State<'a> {
+    fn update(&mut self, escape: &'a str) {
+        if escape.starts_with("\x1B[") && escape.ends_with('m') {
+            let params = &escape[2..escape.len() - 1];
+            let mut codes = params.split(';');
+            if let Some("") | Some("0") = codes.next() {
+                self.sgr.clear();
+                if codes.next().is_none() {
+                    return;
+                }
+            }
+            self.sgr.push(escape);
+        } else if let Some(params) = escape.strip_prefix("\x1B]8;") {
+            // "params;url" then the terminator, the url being empty at the end of the link
+            let url = params.find(';').map_or("", |idx| &params[idx + 1..]);
+            self.link = if url.trim_end_matches(&['\x1B', '\\', '\x07'][..]).is_empty() {
+                None
+            } else {
+                Some(escape)
+            };
+        }
+    }
+
+    /// The sequences starting a line.
+    fn open(&self, line: &mut String) {
+        if let Some(link) = self.link {
+            line.push_str(link);
+        }
+        for sgr in &self.sgr {
+            line.push_str(sgr);
+        }
+    }
+
+    /// The sequences ending a line.
+    fn close(&self, line: &mut String) {
+        if !self.sgr.is_empty() {
+            line.push_str("\x1B[0m");
+        }
+        if self.link.is_some() {
+            line.push_str("\x1B]8;;\x1B\\");
+        }
+    }
+}
+
+/// A word, with the escape sequences found in it and before it.
+#[derive(Clone, Debug, Default)]
+struct Word<'a> {
+    tokens: Vec<Token<'a>>,
+    width: usize,
+}
+
+impl<'a> Word<'a> {
+    fn push(&mut self, token: Token<'a>) {
+        if let Token::Char(c) = token {
+            self.width += char_width(c);
+        }
+        self.tokens.push(token);
+    }
+
+    /// Cuts the start of a word too wide for its line.
+    fn split_at_width(&mut self, width: usize) -> Word<'a> {
+        let mut head = Word::default();
+        let mut idx = 0;
+        while idx < self.tokens.len() {
+            if let Token::Char(c) = self.tokens[idx] {
+                // at least a character per line, even when it's too wide
+                if head.width + char_width(c) > width && head.width > 0 {
+                    break;
+                }
+            }
+            head.push(self.tokens[idx]);
+            idx += 1;
+        }
+        self.tokens.drain(..idx);
+        self.width -= head.width;
+        head
+    }
+}
+
+/// The words of a paragraph. The escape sequences in the spaces go with the next word, the
+/// last ones with the last word.
+fn words<'a>(paragraph: &[Token<'a>]) -> Vec<Word<'a>> {
+    let mut words = Vec::new();
+    let mut word = Word::default();
+    let mut in_word = false;
+
+    for &token in paragraph {
+        match token {
+            Token::Char(c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(word);
+                    word = Word::default();
+                    in_word = false;
+                }
+            }
+            Token::Char(_) => {
+                in_word = true;
+                word.push(token);
+            }
+            Token::Escape(_) => word.push(token),
+        }
+    }
+
+    if in_word || words.is_empty() {
+        words.push(word);
+    } else if let Some(last) = words.last_mut() {
+        last.tokens.extend(word.tokens);
+    }
+    words
+}
+
+/// Splits the words of a paragraph into lines.
+fn lines<'a>(words: Vec<Word<'a>>, options: &WrapOptions) -> Vec<Vec<Word<'a>>> {
+    let mut lines = Vec::new();
+    let mut line: Vec<Word> = Vec::new();
+    let mut line_width = 0;
+
+    for mut word in words {
+        loop {
+            let available = options.available(lines.len());
+            let needed = if line.is_empty() {
+                word.width
+            } else {
+                line_width + 1 + word.width
+            };
+
+            if needed <= available {
+                line_width = needed;
+                line.push(word);
+                break;
+            } else if !line.is_empty() {
+                lines.push(line);
+                line = Vec::new();
+                line_width = 0;
+            } else {
+                let head = word.split_at_width(available);
+                lines.push(vec![head]);
+            }
+        }
+    }
+    lines.push(line);
+    lines
+}
+
+/// Wraps `text` in lines of at most `options.width` columns. Words are cut only when they are
+/// wider than a line, and newlines start new paragraphs.
+///
+/// `text` can be a `ColoredString`, or any string holding escape sequences, like the `format!`
+/// of several of them.
+pub fn wrap<T: fmt::Display + ?Sized>(text: &T, options: &WrapOptions) -> Vec<String> {
+    let text = text.to_string();
+    let all_tokens: Vec<Token> = tokens(&text).collect();
+    let mut state = State::default();
+    let mut res = Vec::new();
+
+    for paragraph in all_tokens.split(|&token| token == Token::Char('\n')) {
+        let lines = lines(words(paragraph), options);
+        let count = lines.len();
+
+        for (idx, words) in lines.into_iter().enumerate() {
+            let available = options.available(idx);
+            let words_width: usize = words.iter().map(|word| word.width).sum();
+            let spaces = words.len().saturating_sub(1);
+            let extra = available.saturating_sub(words_width + spaces);
+
+            let (left_pad, gaps): (usize, Vec<usize>) = match options.justify {
+                Justify::Left => (0, vec![1; spaces]),
+                Justify::Right => (extra, vec![1; spaces]),
+                Justify::Center => (extra / 2, vec![1; spaces]),
+                Justify::Full if idx + 1 == count || spaces == 0 => (0, vec![1; spaces]),
+                Justify::Full => {
+                    let gaps = (0..spaces)
+                        .map(|gap| 1 + extra / spaces + usize::from(gap < extra % spaces))
+                        .collect();
+                    (0, gaps)
+                }
+            };
+
+            let mut line = " ".repeat(options.indent_of(idx) + left_pad);
+            state.open(&mut line);
+            for (idx, word) in words.iter().enumerate() {
+                if idx > 0 {
+                    line.push_str(&" ".repeat(gaps[idx - 1]));
+                }
+                for &token in &word.tokens {
+                    match token {
+                        Token::Char(c) => line.push(c),
+                        Token::Escape(escape) => {
+                            state.update(escape);
+                            line.push_str(escape);
+                        }
+                    }
+                }
+            }
+            state.close(&mut line);
+
+            if words_width == 0 {
+                line.clear();
+            }
+            res.push(line);
+        }
+    }
+    res
+}
+
+/// Splits `text` at its newlines, each line opening the styles and the link active at its start
+/// and closing them at its end, as the lines of `wrap` do.
+pub(crate) fn split_lines(text: &str) -> Vec<String> {
+    let mut state = State::default();
+    text.split('\n')
+        .map(|paragraph| {
+            let mut line = String::new();
+            state.open(&mut line);
+            for token in tokens(paragraph) {
+                match token {
+                    Token::Char(c) => line.push(c),
+                    Token::Escape(escape) => {
+                        state.update(escape);
+                        line.push_str(escape);
+                    }
+                }
+            }
+            state.close(&mut line);
+            line
+        })
+        .collect()
+}
+
+/// Like `wrap`, the lines being joined by newlines.
+pub fn fill<T: fmt::Display + ?Sized>(text: &T, options: &WrapOptions) -> String {
+    wrap(text, options).join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use width::{strip_escapes, visible_width};
+    use Colorize;
+
+    fn plain(text: &str, options: WrapOptions) -> Vec<String> {
+        wrap(text, &options)
+    }
+
+    #[test]
+    fn wrap_plain_text() {
+        assert_eq!(
+            vec!["the quick", "brown fox", "jumps"],
+            plain("the quick brown fox jumps", WrapOptions::new(10))
+        );
+        assert_eq!(
+            vec!["a", "", "b c"],
+            plain("a\n\nb   c", WrapOptions::new(10))
+        );
+        assert_eq!(vec![""], plain("", WrapOptions::new(10)));
+    }
+
+    #[test]
+    fn long_words() {
+        assert_eq!(
+            vec!["a", "abcd", "efgh", "ij b"],
+            plain("a abcdefghij b", WrapOptions::new(4))
+        );
+        assert_eq!(vec!["日", "本"], plain("日本", WrapOptions::new(3)));
+    }
+
+    #[test]
+    fn indents() {
+        let options = WrapOptions::new(10).indent(2).hanging_indent(2);
+        assert_eq!(
+            vec!["  the", "    quick", "    brown", "    fox"],
+            plain("the quick brown fox", options)
+        );
+    }
+
+    #[test]
+    fn justification() {
+        let text = "aa bb c ddd ee";
+        assert_eq!(
+            vec!["aa   bb  c", "ddd ee"],
+            plain(text, WrapOptions::new(10).justify(Justify::Full))
+        );
+        assert_eq!(
+            vec!["   aa bb c", "    ddd ee"],
+            plain(text, WrapOptions::new(10).justify(Justify::Right))
+        );
+        assert_eq!(
+            vec![" aa bb c", "  ddd ee"],
+            plain(text, WrapOptions::new(10).justify(Justify::Center))
+        );
+    }
+
+    #[test]
+    fn styles_reopened_on_each_line() {
+        let text = format!("{} {}", "one two three".red(), "four".bold());
+        let lines = wrap(&text, &WrapOptions::new(10));
+
+        assert_eq!(
+            vec![
+                "\x1B[31mone two\x1B[0m".to_string(),
+                "\x1B[31mthree\x1B[0m \x1B[1mfour\x1B[0m".to_string(),
+            ],
+            lines
+        );
+    }
+
+    #[test]
+    fn nested_styles() {
+        let text = format!("aa {} bb", "cc dd".bold()).on_blue();
+        let lines = wrap(&text, &WrapOptions::new(6));
+
+        assert_eq!(
+            vec!["aa cc", "dd bb"],
+            lines
+                .iter()
+                .map(|line| strip_escapes(line))
+                .collect::<Vec<_>>()
+        );
+        // the second line starts blue and bold, goes back to blue after the inner reset
+        assert_eq!("\x1B[44m\x1B[1mdd\x1B[0m\x1B[44m bb\x1B[0m", lines[1]);
+        assert!(lines.iter().all(|line| visible_width(line) <= 6));
+    }
+
+    #[test]
+    fn hyperlinks_reopened() {
+        let text = "\x1B]8;;https://a\x1B\\aa bb\x1B]8;;\x1B\\ c";
+        assert_eq!(
+            vec![
+                "\x1B]8;;https://a\x1B\\aa\x1B]8;;\x1B\\",
+                "\x1B]8;;https://a\x1B\\bb\x1B]8;;\x1B\\ c",
+            ],
+            plain(text, WrapOptions::new(4))
+        );
+    }
+}
@@ -119,3 +119,31 @@ mod compat_complex {
         );
     }
 }
+
+// ansi_term doesn't know about these attributes, so we check the raw sequences
+// against the ones kitty, WezTerm and VTE document.
+macro_rules! test_extended_style {
+    ($string:expr, $style:ident, $code:expr) => {
+        #[test]
+        fn $style() {
+            let s = format!("{} {}", $string, stringify!($style));
+            assert_eq!(
+                s.$style().to_string(),
+                format!("\x1B[{}m{}\x1B[0m", $code, s)
+            )
+        }
+    };
+}
+
+mod compat_extended_styles {
+    use super::colored::*;
+
+    test_extended_style!("test string", double_underline, "21");
+    test_extended_style!("test string", curly_underline, "4:3");
+    test_extended_style!("test string", dotted_underline, "4:4");
+    test_extended_style!("test string", dashed_underline, "4:5");
+    test_extended_style!("test string", overline, "53");
+    test_extended_style!("test string", rapid_blink, "6");
+    test_extended_style!("test string", framed, "51");
+    test_extended_style!("test string", encircled, "52");
+}
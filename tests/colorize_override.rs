@@ -0,0 +1,24 @@
+//! The tests changing `control::SHOULD_COLORIZE`, which is global to the process: they run in
+//! their own test binary, one after the other in a single test, not to race with the others.
+#![cfg(not(feature = "no-color"))]
+
+extern crate colored;
+
+use colored::control;
+use colored::cursor::{clear_line, up};
+use colored::Colorize;
+
+#[test]
+fn commands_follow_the_override() {
+    control::SHOULD_COLORIZE.set_override(true);
+    assert_eq!("\x1B[2A\x1B[2K", format!("{}{}", up(2), clear_line()));
+    assert_eq!("\x1B[31mx\x1B[0m", "x".red().to_string());
+
+    control::SHOULD_COLORIZE.set_override(false);
+    assert_eq!("", format!("{}{}", up(2), clear_line()));
+    assert_eq!("x", "x".red().to_string());
+    // the escape is still available, for the callers which decide themselves
+    assert_eq!("\x1B[2A", up(2).to_escape());
+
+    control::SHOULD_COLORIZE.unset_override();
+}